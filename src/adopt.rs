@@ -0,0 +1,84 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Adopting license files that aren't named LICENSE.
+
+use std::path::{Path, PathBuf};
+use yaml_front_matter::Document;
+
+use crate::headers::standard_header;
+use crate::identify::{identify, CONFIDENCE_THRESHOLD};
+use crate::license::LicenseInfo;
+use crate::placeholders::Placeholders;
+use crate::{link_preamble, prepare_overwrite, PreambleLayout};
+
+/// Other common names of license files, in order of preference.
+static LICENSE_FILE_NAMES: [&str; 10] = [
+    "LICENSE.md",
+    "LICENSE.txt",
+    "LICENSE.rst",
+    "LICENCE",
+    "LICENCE.md",
+    "LICENCE.txt",
+    "COPYING",
+    "COPYING.md",
+    "COPYING.txt",
+    "UNLICENSE",
+];
+
+/// The license file in `dir` under one of the other common names, if any.
+pub fn find_license_file(dir: &Path) -> Option<PathBuf> {
+    LICENSE_FILE_NAMES.iter()
+        .map(|file_name| dir.join(file_name))
+        .find(|path| path.is_file())
+}
+
+/// Writes PREAMBLE for an existing license file as laid out, returning
+/// `false` if PREAMBLE already exists and `force` isn't set. The header
+/// layout uses the standard header of the license the file is identified
+/// as, with its placeholders filled in, and a copy of the file otherwise.
+pub fn adopt_license_file(
+    licenses: &[Document<LicenseInfo>],
+    placeholders: &Placeholders,
+    license_path: &Path,
+    preamble_path: &Path,
+    layout: PreambleLayout,
+    force: bool,
+) -> bool {
+    if !prepare_overwrite(preamble_path, force) {
+        return false;
+    }
+    let license_contents = std::fs::read_to_string(license_path).unwrap();
+    let header = identify(&license_contents, licenses)
+        .filter(|(_, confidence)| *confidence >= CONFIDENCE_THRESHOLD)
+        .and_then(|(license_document, _)| standard_header(&license_document.metadata.spdx_id))
+        .map(|header| placeholders.fill(&header));
+    match (layout, header) {
+        (PreambleLayout::Header, Some(header)) => std::fs::write(preamble_path, header).unwrap(),
+        (PreambleLayout::Header | PreambleLayout::Full, _) => {
+            std::fs::write(preamble_path, license_contents.trim()).unwrap()
+        }
+        (PreambleLayout::Symlink, _) => link_preamble(license_path, preamble_path),
+    }
+    println!("Wrote {} for {}", preamble_path.display(), license_path.display());
+    true
+}
@@ -0,0 +1,128 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Backups of the files modified by `add`, restored by `undo`.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+/// Where backups are kept, one directory per run, relative to the project
+/// root.
+pub const BACKUPS_DIR: &str = ".license-preamble/backups";
+
+const BACKUP_FILE: &str = "backup.toml";
+
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    path: PathBuf,
+    original: String,
+    written: String,
+}
+
+/// The files modified in a single run.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Backup {
+    files: Vec<BackupFile>,
+}
+
+impl Backup {
+    /// Records a file's contents before and after it was modified.
+    pub fn record(&mut self, path: &Path, original: &str, written: &str) {
+        self.files.push(BackupFile {
+            path: path.to_path_buf(),
+            original: original.to_string(),
+            written: written.to_string(),
+        });
+    }
+
+    /// Saves the backup under a new run id, which sorts after every earlier
+    /// run. Nothing is saved when no file was modified.
+    pub fn save(&self, backups_dir: &Path) {
+        if self.files.is_empty() {
+            return;
+        }
+        let run_id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let run_dir = backups_dir.join(format!("{run_id:020}"));
+        std::fs::create_dir_all(&run_dir).unwrap();
+        std::fs::write(run_dir.join(BACKUP_FILE), toml::to_string(self).unwrap()).unwrap();
+        println!("Saved a backup of {} file(s) in {}", self.files.len(), run_dir.display());
+    }
+}
+
+/// Restores the files from the most recent backup and deletes it. Unless
+/// `force` is set, nothing is restored if any of the files changed since it
+/// was written.
+pub fn undo(backups_dir: &Path, force: bool) -> bool {
+    let latest = std::fs::read_dir(backups_dir).into_iter().flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join(BACKUP_FILE).is_file())
+        .max();
+    let Some(run_dir) = latest else {
+        eprintln!("No backups to undo, run `add --backup` to create one");
+        return false;
+    };
+
+    let backup_path = run_dir.join(BACKUP_FILE);
+    let backup = std::fs::read_to_string(&backup_path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| toml::from_str::<Backup>(&contents).map_err(|err| err.to_string()));
+    let backup = match backup {
+        Ok(backup) => backup,
+        Err(err) => {
+            eprintln!("Invalid backup in {}: {err}", run_dir.display());
+            return false;
+        }
+    };
+
+    let changed: Vec<&BackupFile> = backup.files.iter()
+        .filter(|file| std::fs::read_to_string(&file.path).map_or(true, |contents| contents != file.written))
+        .collect();
+    if !changed.is_empty() && !force {
+        eprintln!("Refusing to undo, these files changed since the preamble was added:");
+        for file in changed {
+            eprintln!("{}", file.path.display());
+        }
+        eprintln!("Pass --force to restore them anyway");
+        return false;
+    }
+
+    let mut errors = 0;
+    for file in &backup.files {
+        println!("Restoring file {:?}", file.path);
+        if let Err(err) = std::fs::write(&file.path, &file.original) {
+            eprintln!("Failed to write {:?}: {err}", file.path);
+            errors += 1;
+        }
+    }
+    if errors > 0 {
+        // The backup is kept so that the files that failed can be restored
+        // with another `undo --force`.
+        eprintln!("Kept the backup in {}", run_dir.display());
+        return false;
+    }
+    if let Err(err) = std::fs::remove_dir_all(&run_dir) {
+        eprintln!("Warning: couldn't delete the backup in {}: {err}", run_dir.display());
+    }
+    true
+}
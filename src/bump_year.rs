@@ -0,0 +1,144 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Updating the copyright years in LICENSE, PREAMBLE and file preambles.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::preamble::{split_leading_comment, split_prologue};
+use crate::{read_source_file_or_warn, source_files, write_source_file};
+
+/// The current year in UTC.
+pub fn current_year() -> u16 {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() / 86_400;
+    // Howard Hinnant's days to civil date conversion, shifted so that years
+    // start in March and leap days fall at their end.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400 + u64::from(month >= 10);
+    year as u16
+}
+
+/// Parses the year at the start of `text`, unless it's part of a longer
+/// number.
+fn year_at(text: &str) -> Option<u16> {
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    (digits == 4).then(|| text[..4].parse().ok()).flatten()
+}
+
+/// Extends the year or year range following "Copyright" in `line` to end at
+/// `year`, or returns `None` if there's nothing to update.
+fn bump_line(line: &str, year: u16) -> Option<String> {
+    let lowercase = line.to_lowercase();
+    if lowercase.len() != line.len() {
+        return None;
+    }
+    let after_copyright = lowercase.find("copyright")? + "copyright".len();
+    let start = after_copyright + line[after_copyright..].find(|c: char| c.is_ascii_digit())?;
+    let first = year_at(&line[start..])?;
+
+    let mut end = start + 4;
+    let mut last = first;
+    if let Some(range_end) = line[end..].strip_prefix('-').and_then(year_at) {
+        last = range_end;
+        end += 5;
+    }
+    if last >= year {
+        return None;
+    }
+    Some(format!("{}{first}-{year}{}", &line[..start], &line[end..]))
+}
+
+/// Bumps every copyright line of `text`, returning `None` if none changed.
+fn bump_text(text: &str, year: u16) -> Option<String> {
+    let mut changed = false;
+    let bumped: String = text.split_inclusive('\n').map(|line| match bump_line(line, year) {
+        Some(line) => {
+            changed = true;
+            line
+        }
+        None => line.to_string(),
+    }).collect();
+    changed.then_some(bumped)
+}
+
+/// Updates the copyright lines in LICENSE, PREAMBLE and the leading comment
+/// block of each source file to end at `year`, the current year by default.
+/// Nothing after a file's leading comment block is touched. Returns whether
+/// every file could be written.
+pub fn bump_year(
+    config: &Config,
+    license_path: &Path,
+    preamble_path: &Path,
+    source_root: Option<Vec<String>>,
+    year: Option<u16>,
+) -> bool {
+    let year = year.unwrap_or_else(current_year);
+    let mut errors = 0;
+
+    let preamble_is_symlink = preamble_path.symlink_metadata().is_ok_and(|metadata| metadata.is_symlink());
+    // A PREAMBLE linked to LICENSE is updated through it.
+    let paths = if preamble_is_symlink { vec![license_path] } else { vec![license_path, preamble_path] };
+    for path in paths {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        if let Some(bumped) = bump_text(&contents, year) {
+            println!("Bumping copyright year in {path:?}");
+            if !write_source_file(path, &bumped) {
+                errors += 1;
+            }
+        }
+    }
+
+    let mut bumped_files = 0;
+    for (path, comment_syntax) in source_files(config, source_root) {
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        let body = split_prologue(&file_contents, comment_syntax).1;
+        let Some((header, _)) = split_leading_comment(body, comment_syntax) else {
+            continue;
+        };
+        let Some(bumped) = bump_text(header, year) else {
+            continue;
+        };
+
+        println!("Bumping copyright year in file {path:?}");
+        let head = &file_contents[..file_contents.len() - body.len()];
+        let new_contents = format!("{head}{bumped}{}", &body[header.len()..]);
+        if write_source_file(&path, &new_contents) {
+            bumped_files += 1;
+        } else {
+            errors += 1;
+        }
+    }
+
+    println!("Bumped the copyright year to {year} in {bumped_files} file(s)");
+    errors == 0
+}
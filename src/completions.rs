@@ -0,0 +1,73 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Shell completion scripts generated from the clap definitions.
+
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{Arg, Command};
+use clap_complete::Shell;
+use yaml_front_matter::Document;
+
+use crate::license::LicenseInfo;
+
+/// The value name marking arguments that take a license name, which are
+/// completed against the embedded licenses.
+pub const LICENSE_VALUE_NAME: &str = "LICENSE";
+
+/// Prints the completion script for `shell` to stdout.
+pub fn print_completions(shell: Shell, mut command: Command, licenses: &[Document<LicenseInfo>]) {
+    let names = PossibleValuesParser::new(licenses.iter().flat_map(|document| {
+        let info = &document.metadata;
+        [
+            PossibleValue::new(info.spdx_id.clone()).help(info.title.clone()),
+            PossibleValue::new(info.title.clone()),
+        ]
+    }));
+    command = complete_licenses(command, &names);
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+/// Makes every license argument of `command` and its subcommands complete
+/// to `names`.
+fn complete_licenses(mut command: Command, names: &PossibleValuesParser) -> Command {
+    let arguments: Vec<String> = command.get_arguments()
+        .filter(|arg| is_license(arg))
+        .map(|arg| arg.get_id().to_string())
+        .collect();
+    for argument in arguments {
+        command = command.mut_arg(argument, |arg| arg.value_parser(names.clone()));
+    }
+
+    let subcommands: Vec<String> = command.get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect();
+    for subcommand in subcommands {
+        command = command.mut_subcommand(subcommand, |subcommand| complete_licenses(subcommand, names));
+    }
+    command
+}
+
+fn is_license(arg: &Arg) -> bool {
+    arg.get_value_names()
+        .is_some_and(|names| names.iter().any(|name| name == LICENSE_VALUE_NAME))
+}
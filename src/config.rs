@@ -0,0 +1,195 @@
+//! The project configuration file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::workspace_source_roots;
+use crate::markers::Markers;
+use crate::preamble::LONG_PREAMBLE_LINES;
+use crate::style::{Border, CommentStyle, RULE_CHARACTERS};
+use crate::{EXTENSIONS, FILENAMES};
+
+/// Where the configuration is read from, relative to the project root.
+pub const CONFIG_PATH: &str = ".license-preamble.toml";
+
+/// The default `max-file-size`, 4 MiB.
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Project settings used whenever the matching flags aren't given.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// The SPDX id of the project's license.
+    pub license: Option<String>,
+    /// Whether LICENSE holds a license that isn't one of the embedded ones.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub custom_license: bool,
+    /// The file whose contents `add` inserts, PREAMBLE by default.
+    pub preamble: Option<PathBuf>,
+    /// The copyright holder filling in `{{holder}}` in PREAMBLE, git's
+    /// `user.name` by default.
+    pub holder: Option<String>,
+    /// Values for the `{{variables}}` in PREAMBLE.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// The directories searched for source files.
+    pub source_roots: Vec<String>,
+    /// Names of directories that are never walked, in addition to the
+    /// dependency and build directories skipped by default.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_dirs: Vec<String>,
+    /// Names of directories holding third-party code, which are never
+    /// walked unless `--include-vendored` is given.
+    pub vendored_dirs: Vec<String>,
+    /// Whether files with a generated marker such as `@generated` or
+    /// `DO NOT EDIT` near the top are skipped.
+    pub skip_generated: bool,
+    /// Markers of generated files, in addition to the default ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub generated_markers: Vec<String>,
+    /// How many blank lines separate the preamble from the rest of a file.
+    pub blank_lines: usize,
+    /// Preambles longer than this are only added with
+    /// `--allow-long-preamble`.
+    pub max_preamble_lines: usize,
+    /// Files larger than this many bytes are skipped, 0 meaning no limit.
+    pub max_file_size: u64,
+    /// The comment syntax used for each file extension.
+    pub extensions: BTreeMap<String, String>,
+    /// The comment syntax used for files with these names, in addition to
+    /// the built-in ones such as `Makefile`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub filenames: BTreeMap<String, String>,
+    /// Whether the preamble is written with line or block comments, for the
+    /// extensions of languages with both.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub styles: BTreeMap<String, CommentStyle>,
+    /// The marker comments `add` puts around the preamble, so that
+    /// `update` and `remove` can find it whatever its contents. Off unless
+    /// a `[markers]` table is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markers: Option<Markers>,
+    /// The rules `add` puts above and below the preamble. Off unless a
+    /// `[border]` table is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<Border>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            license: None,
+            custom_license: false,
+            preamble: None,
+            holder: None,
+            variables: BTreeMap::new(),
+            source_roots: vec![String::from("src"), String::from("lib")],
+            exclude_dirs: vec![],
+            vendored_dirs: ["third_party", "3rdparty", "extern", "vendored"].map(String::from).to_vec(),
+            skip_generated: true,
+            generated_markers: vec![],
+            blank_lines: 1,
+            max_preamble_lines: LONG_PREAMBLE_LINES,
+            max_file_size: MAX_FILE_SIZE,
+            extensions: EXTENSIONS.iter()
+                .map(|(extension, comment_syntax)| (extension.to_string(), comment_syntax.to_string()))
+                .collect(),
+            filenames: BTreeMap::new(),
+            styles: BTreeMap::new(),
+            markers: None,
+            border: None,
+        }
+    }
+}
+
+impl Config {
+    /// The comment syntax for a file, or `None` if neither its extension
+    /// nor its name is supported.
+    pub fn comment_syntax(&self, path: &Path) -> Option<&str> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        extension.and_then(|extension| self.extensions.get(extension))
+            .map(String::as_str)
+            .or_else(|| self.filename_comment_syntax(path.file_name()?.to_str()?))
+    }
+
+    /// The comment syntax for files named `file_name`, from the
+    /// configuration or the built-in table.
+    pub fn filename_comment_syntax(&self, file_name: &str) -> Option<&str> {
+        self.filenames.get(file_name).map(String::as_str).or_else(|| {
+            FILENAMES.iter()
+                .find(|(name, _)| *name == file_name)
+                .map(|(_, comment_syntax)| *comment_syntax)
+        })
+    }
+
+    /// Switches the comment syntax of every extension to `style`, or to the
+    /// one set for it under `styles` when no style is given.
+    pub fn apply_styles(&mut self, style: Option<CommentStyle>) {
+        let styles = &self.styles;
+        for (extension, comment_syntax) in &mut self.extensions {
+            let Some(style) = style.or_else(|| styles.get(extension).copied()) else {
+                continue;
+            };
+            if let Some(styled) = style.comment_syntax(extension, comment_syntax) {
+                *comment_syntax = styled.to_string();
+            }
+        }
+    }
+
+    /// The source roots walked when none are given. When they're left at
+    /// the default in a Cargo workspace, they're the source directories of
+    /// its members instead.
+    pub fn default_source_roots(&self) -> Vec<String> {
+        if self.source_roots != Config::default().source_roots {
+            return self.source_roots.clone();
+        }
+        match workspace_source_roots(Path::new("")) {
+            Some(source_roots) if !source_roots.is_empty() => {
+                eprintln!("Using the Cargo workspace's source roots: {}", source_roots.join(", "));
+                source_roots
+            }
+            _ => self.source_roots.clone(),
+        }
+    }
+
+    /// Reads the configuration, falling back to the defaults when the file
+    /// doesn't exist and exiting with an error when it's invalid.
+    pub fn load(config_path: &Path) -> Config {
+        match std::fs::read_to_string(config_path) {
+            Ok(contents) => {
+                let config: Config = toml::from_str(&contents).unwrap_or_else(|err| {
+                    eprintln!("Invalid {}: {err}", config_path.display());
+                    std::process::exit(1);
+                });
+                if let Some(border) = config.border.as_ref().filter(|border| !RULE_CHARACTERS.contains(&border.character)) {
+                    eprintln!(
+                        "Invalid {}: the border character {:?} isn't one of {RULE_CHARACTERS:?}",
+                        config_path.display(),
+                        border.character,
+                    );
+                    std::process::exit(1);
+                }
+                config
+            }
+            Err(_) => Config::default(),
+        }
+    }
+
+    pub fn save(&self, config_path: &Path) {
+        std::fs::write(config_path, toml::to_string(self).unwrap()).unwrap();
+    }
+}
+
+/// Writes the default configuration for `license`, returning `false` if
+/// the file already exists and `force` isn't set.
+pub fn config_init(config_path: &Path, license: Option<String>, force: bool) -> bool {
+    if config_path.exists() && !force {
+        eprintln!("Refusing to overwrite {}, pass --force to replace it", config_path.display());
+        return false;
+    }
+
+    Config { license, ..Config::default() }.save(config_path);
+    println!("Wrote {}", config_path.display());
+    true
+}
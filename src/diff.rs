@@ -0,0 +1,114 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Previewing the changes `add` would make as a unified diff.
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::preamble::prefix_preamble;
+use crate::scopes::Preambles;
+use crate::{add_decision, is_too_large, read_source_file_or_warn, source_files, AddDecision, AddRules};
+
+/// Lines of unchanged context around each change.
+const CONTEXT: usize = 3;
+
+/// The `start,length` range of a hunk, where an empty range starts at the
+/// line before it.
+fn range(start: usize, length: usize) -> String {
+    if length == 0 {
+        format!("{},0", start - 1)
+    } else {
+        format!("{start},{length}")
+    }
+}
+
+/// Renders the change from `old` to `new` as a unified diff with a single
+/// hunk spanning from the first to the last changed line.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let suffix = old_lines[prefix..].iter().rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let before = prefix.min(CONTEXT);
+    let after = suffix.min(CONTEXT);
+    let start = prefix - before;
+    let old_end = old_lines.len() - suffix + after;
+    let new_end = new_lines.len() - suffix + after;
+
+    let mut diff = format!("--- {}\n+++ {}\n", path.display(), path.display());
+    diff += &format!("@@ -{} +{} @@\n", range(start + 1, old_end - start), range(start + 1, new_end - start));
+    let mut push_line = |marker: char, line: &str| {
+        diff.push(marker);
+        diff += line;
+        if !line.ends_with('\n') {
+            diff += "\n\\ No newline at end of file\n";
+        }
+    };
+    for line in &old_lines[start..prefix] {
+        push_line(' ', line);
+    }
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        push_line('-', line);
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        push_line('+', line);
+    }
+    for line in &old_lines[old_lines.len() - suffix..old_end] {
+        push_line(' ', line);
+    }
+    diff
+}
+
+/// Prints the diff of every file `add` would change, or only their paths
+/// with `name_only`. Nothing is written.
+pub fn diff(config: &Config, preamble_path: &Path, source_root: Option<Vec<String>>, name_only: bool) {
+    let mut preambles = Preambles::new(config, preamble_path, &[]);
+
+    for (path, comment_syntax) in source_files(config, source_root) {
+        let Some((_, preamble_contents, _)) = preambles.get(&path) else {
+            continue;
+        };
+        let prefixed_preamble = prefix_preamble(preamble_contents, comment_syntax);
+        if is_too_large(&path, config.max_file_size) {
+            continue;
+        }
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        let rules = AddRules::default();
+        let new_contents = match add_decision(config, &path, &file_contents, &prefixed_preamble, comment_syntax, &rules) {
+            AddDecision::Add(new_contents) | AddDecision::Replace(new_contents) => new_contents,
+            _ => continue,
+        };
+
+        if name_only {
+            println!("{}", path.display());
+        } else {
+            print!("{}", unified_diff(&path, &file_contents, &new_contents));
+        }
+    }
+}
@@ -0,0 +1,185 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Validation of the project setup.
+
+use std::path::Path;
+use yaml_front_matter::Document;
+
+use crate::config::Config;
+use crate::generated::{has_ignore_directive, is_generated, looks_minified};
+use crate::identify::{identify, identify_preamble, CONFIDENCE_THRESHOLD};
+use crate::license::LicenseInfo;
+use crate::preamble::{prefix_preamble, presence, starts_with_inline_html, Presence};
+use crate::{license_files, read_source_file_or_warn, source_files};
+use crate::template::try_render_preamble;
+
+/// How many source files are sampled for an up to date preamble.
+const SAMPLE_SIZE: usize = 5;
+
+/// Collects the problems found along with suggested fixes.
+#[derive(Default)]
+struct Report {
+    problems: usize,
+}
+
+impl Report {
+    fn ok(&self, message: &str) {
+        println!("ok: {message}");
+    }
+
+    fn warning(&self, message: &str, note: &str) {
+        println!("warning: {message}");
+        println!("  note: {note}");
+    }
+
+    fn problem(&mut self, message: &str, fix: &str) {
+        println!("error: {message}");
+        println!("  fix: {fix}");
+        self.problems += 1;
+    }
+}
+
+/// Checks the LICENSE, or the files of a dual license, PREAMBLE and a sample
+/// of source files, returning whether everything is set up correctly.
+/// Files opted out or skipped as generated or minified aren't sampled.
+pub fn doctor(
+    licenses: &[Document<LicenseInfo>],
+    config: &Config,
+    license_path: &Path,
+    preamble_path: &Path,
+    source_root: Option<Vec<String>>,
+) -> bool {
+    let mut report = Report::default();
+
+    for license_path in license_files(license_path) {
+        let name = license_path.display();
+        match std::fs::read_to_string(&license_path) {
+            Ok(license_contents) if config.custom_license => {
+                if license_contents.trim().is_empty() {
+                    report.problem(&format!("{name} is empty"), "run `init --license-file <path> --force`");
+                } else {
+                    report.ok(&format!("{name} is a custom license"));
+                }
+            }
+            Ok(license_contents) => match identify(&license_contents, licenses) {
+                Some((license_document, confidence)) if confidence >= CONFIDENCE_THRESHOLD => {
+                    report.ok(&format!("{name} is the {}", license_document.metadata.title));
+                }
+                _ => report.problem(
+                    &format!("{name} doesn't match any known license"),
+                    "compare it with `show <license>`, or recreate it with `init`",
+                ),
+            },
+            Err(_) => report.problem(&format!("{name} is missing"), "run `init <license>`"),
+        }
+    }
+
+    let is_symlink = preamble_path.symlink_metadata().is_ok_and(|metadata| metadata.is_symlink());
+    let preamble_contents = match std::fs::read_to_string(preamble_path) {
+        Ok(contents) if contents.trim().is_empty() => {
+            report.problem("PREAMBLE is empty", "write the header text into PREAMBLE, or remove it and run `init`");
+            None
+        }
+        Ok(contents) => {
+            report.ok("PREAMBLE is readable");
+            if is_symlink {
+                report.warning(
+                    "PREAMBLE is a symlink",
+                    "customizing PREAMBLE would also edit the file it links to, replace it with a copy to avoid that",
+                );
+            }
+            if !config.custom_license {
+                match identify_preamble(&contents, licenses) {
+                    Some(name) => report.ok(&format!("PREAMBLE matches the {name}")),
+                    None => report.warning(
+                        "PREAMBLE doesn't match any known license",
+                        "recreate it with `init <license> --force`, unless it was customized on purpose",
+                    ),
+                }
+            }
+            match try_render_preamble(config, &contents, &[]) {
+                Ok(rendered) => Some(rendered),
+                Err(unresolved) => {
+                    report.problem(
+                        &format!("PREAMBLE uses variables without a value: {}", unresolved.join(", ")),
+                        "set them under [variables] in the configuration file",
+                    );
+                    None
+                }
+            }
+        }
+        Err(_) if is_symlink => {
+            report.problem("PREAMBLE is a broken symlink", "remove PREAMBLE and run `init <license>`");
+            None
+        }
+        Err(_) if preamble_path.exists() => {
+            report.problem("PREAMBLE can't be read", "check the file's permissions and encoding");
+            None
+        }
+        Err(_) => {
+            report.problem("PREAMBLE is missing", "run `init <license>`");
+            None
+        }
+    };
+
+    if let Some(preamble_contents) = preamble_contents {
+        let mut sampled = 0;
+        let mut failures = vec![];
+        for (path, comment_syntax) in source_files(config, source_root) {
+            if sampled == SAMPLE_SIZE {
+                break;
+            }
+            let Some(file_contents) = read_source_file_or_warn(&path) else {
+                continue;
+            };
+            let is_skipped = has_ignore_directive(&file_contents)
+                || starts_with_inline_html(&path, &file_contents, comment_syntax)
+                || is_generated(config, &file_contents)
+                || looks_minified(&path, &file_contents);
+            if is_skipped {
+                continue;
+            }
+            sampled += 1;
+            let prefixed_preamble = prefix_preamble(&preamble_contents, comment_syntax);
+            let presence = match &config.markers {
+                Some(markers) => markers.presence(&file_contents, &prefixed_preamble, comment_syntax),
+                None => presence(&file_contents, &prefixed_preamble, comment_syntax, false),
+            };
+            match presence {
+                Presence::Present => {}
+                Presence::Outdated => failures.push((path, "has an outdated preamble", "run `update` to refresh it")),
+                Presence::Missing => failures.push((path, "is missing the preamble", "run `add` to insert it")),
+            }
+        }
+
+        if failures.is_empty() {
+            report.ok(&format!("{sampled} sampled source file(s) have the current preamble"));
+        } else {
+            for (path, message, fix) in failures {
+                report.problem(&format!("{} {message}", path.display()), fix);
+            }
+        }
+    }
+
+    report.problems == 0
+}
@@ -0,0 +1,100 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Filtering licenses by their permissions, conditions and limitations.
+
+use std::collections::BTreeSet;
+use clap::Args;
+use yaml_front_matter::Document;
+
+use crate::license::LicenseInfo;
+
+/// Rules a license must or must not have. Every filter must hold.
+#[derive(Args, Debug)]
+pub struct RuleFilters {
+    /// Only list licenses with this permission, e.g. `patent-use`
+    #[arg(long, value_name = "PERMISSION")]
+    permits: Vec<String>,
+    /// Only list licenses with this condition, e.g. `include-copyright`
+    #[arg(long, value_name = "CONDITION")]
+    requires: Vec<String>,
+    /// Only list licenses with this limitation, e.g. `liability`
+    #[arg(long, value_name = "LIMITATION")]
+    forbids: Vec<String>,
+    /// Only list licenses without this permission
+    #[arg(long, value_name = "PERMISSION")]
+    not_permits: Vec<String>,
+    /// Only list licenses without this condition, e.g. `disclose-source`
+    #[arg(long, value_name = "CONDITION")]
+    not_requires: Vec<String>,
+    /// Only list licenses without this limitation
+    #[arg(long, value_name = "LIMITATION")]
+    not_forbids: Vec<String>,
+}
+
+/// Exits with an error if any of `values` isn't one of the rules of the
+/// embedded licenses.
+fn validate(flag: &str, values: &[String], known: &BTreeSet<&str>) {
+    for value in values {
+        if !known.contains(value.as_str()) {
+            eprintln!("Unknown value `{value}` for --{flag}, expected one of:");
+            for known in known {
+                eprintln!("  {known}");
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+impl RuleFilters {
+    pub fn is_empty(&self) -> bool {
+        [&self.permits, &self.requires, &self.forbids, &self.not_permits, &self.not_requires, &self.not_forbids]
+            .iter()
+            .all(|values| values.is_empty())
+    }
+
+    /// Exits with an error if a filter names a rule that none of the
+    /// licenses has.
+    pub fn validate(&self, licenses: &[Document<LicenseInfo>]) {
+        let infos = || licenses.iter().map(|document| &document.metadata);
+        let permissions: BTreeSet<&str> = infos().flat_map(|info| &info.permissions).map(String::as_str).collect();
+        let conditions: BTreeSet<&str> = infos().flat_map(|info| &info.conditions).map(String::as_str).collect();
+        let limitations: BTreeSet<&str> = infos().flat_map(|info| &info.limitations).map(String::as_str).collect();
+        validate("permits", &self.permits, &permissions);
+        validate("not-permits", &self.not_permits, &permissions);
+        validate("requires", &self.requires, &conditions);
+        validate("not-requires", &self.not_requires, &conditions);
+        validate("forbids", &self.forbids, &limitations);
+        validate("not-forbids", &self.not_forbids, &limitations);
+    }
+
+    pub fn matches(&self, info: &LicenseInfo) -> bool {
+        let all = |values: &[String], rules: &[String]| values.iter().all(|value| rules.contains(value));
+        let none = |values: &[String], rules: &[String]| !values.iter().any(|value| rules.contains(value));
+        all(&self.permits, &info.permissions)
+            && all(&self.requires, &info.conditions)
+            && all(&self.forbids, &info.limitations)
+            && none(&self.not_permits, &info.permissions)
+            && none(&self.not_requires, &info.conditions)
+            && none(&self.not_forbids, &info.limitations)
+    }
+}
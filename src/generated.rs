@@ -0,0 +1,82 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Recognizing generated and minified files, which `add` leaves alone
+//! since the generator would overwrite the preamble anyway, and files opting
+//! out with a directive.
+
+use std::path::Path;
+
+use crate::config::Config;
+
+/// How many lines at the top of a file are searched for a marker.
+const MARKER_LINES: usize = 10;
+
+/// Markers generators put at the top of their output, matched ignoring
+/// case.
+pub static DEFAULT_GENERATED_MARKERS: [&str; 5] = [
+    "@generated",
+    "DO NOT EDIT",
+    "Code generated by",
+    "auto-generated",
+    "autogenerated",
+];
+
+/// Whether a file looks generated, going by the markers in its first lines.
+/// Always `false` when `skip-generated` is turned off.
+pub fn is_generated(config: &Config, file_contents: &str) -> bool {
+    if !config.skip_generated {
+        return false;
+    }
+    let markers: Vec<String> = DEFAULT_GENERATED_MARKERS.iter()
+        .map(|marker| marker.to_string())
+        .chain(config.generated_markers.iter().cloned())
+        .map(|marker| marker.to_lowercase())
+        .collect();
+    file_contents.lines()
+        .take(MARKER_LINES)
+        .map(str::to_lowercase)
+        .any(|line| markers.iter().any(|marker| line.contains(marker.as_str())))
+}
+
+/// The directive, in a comment near the top of a file, making every command
+/// leave it alone.
+pub const IGNORE_DIRECTIVE: &str = "license-preamble: ignore";
+
+/// Whether a file opts out with [`IGNORE_DIRECTIVE`] in its first lines.
+pub fn has_ignore_directive(file_contents: &str) -> bool {
+    file_contents.lines().take(MARKER_LINES).any(|line| line.contains(IGNORE_DIRECTIVE))
+}
+
+/// File name endings of minified and bundled assets.
+static MINIFIED_SUFFIXES: [&str; 3] = [".min.js", ".min.css", ".bundle.js"];
+
+/// Lines longer than this are only found in minified files.
+const MAX_LINE_LENGTH: usize = 5000;
+
+/// Whether a file looks minified or bundled, going by its name or the
+/// length of its longest line.
+pub fn looks_minified(path: &Path, file_contents: &str) -> bool {
+    let file_name = path.file_name().map(|file_name| file_name.to_string_lossy()).unwrap_or_default();
+    MINIFIED_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix))
+        || file_contents.lines().any(|line| line.len() > MAX_LINE_LENGTH)
+}
@@ -0,0 +1,89 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Asking git which files are tracked, changed or staged.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs git, returning its output or, when it fails, its error message.
+fn git(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git").args(args).output()
+        .map_err(|err| format!("couldn't run git: {err}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// The root of the repository containing the current directory.
+pub fn repo_root() -> Result<PathBuf, String> {
+    let output = git(&["rev-parse", "--show-toplevel"])?;
+    let root = PathBuf::from(String::from_utf8_lossy(&output).trim());
+    Ok(std::fs::canonicalize(&root).unwrap_or(root))
+}
+
+/// Splits git's `-z` output into absolute paths, given relative to the root
+/// of the repository.
+fn repo_paths(output: &[u8], root: &Path) -> HashSet<PathBuf> {
+    output.split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| root.join(String::from_utf8_lossy(path).as_ref()))
+        .collect()
+}
+
+/// The absolute paths of the files tracked by git.
+pub fn tracked_files() -> Result<HashSet<PathBuf>, String> {
+    let root = repo_root()?;
+    let output = git(&["-C", &root.to_string_lossy(), "ls-files", "-z"])?;
+    Ok(repo_paths(&output, &root))
+}
+
+/// The absolute paths of the files added, copied, modified or renamed since
+/// `since`, including uncommitted changes. Renamed files are listed under
+/// their new name.
+pub fn changed_files(since: &str) -> Result<HashSet<PathBuf>, String> {
+    let root = repo_root()?;
+    let output = git(&["-C", &root.to_string_lossy(), "diff", "--name-only", "-z", "--diff-filter=ACMR", since, "--"])?;
+    Ok(repo_paths(&output, &root))
+}
+
+/// The absolute paths of the files added, copied, modified or renamed in
+/// the index.
+pub fn staged_files() -> Result<HashSet<PathBuf>, String> {
+    let root = repo_root()?;
+    let output = git(&["-C", &root.to_string_lossy(), "diff", "--cached", "--name-only", "-z", "--diff-filter=ACMR"])?;
+    Ok(repo_paths(&output, &root))
+}
+
+/// The contents of `path` in the index, which may differ from the working
+/// tree.
+pub fn staged_contents(path: &Path) -> Result<String, String> {
+    let root = repo_root()?;
+    let path = std::fs::canonicalize(path).map_err(|err| err.to_string())?;
+    let relative = path.strip_prefix(&root).map_err(|_| format!("{} isn't in the repository", path.display()))?;
+    // Index paths always use forward slashes.
+    let object = format!(":{}", relative.to_string_lossy().replace('\\', "/"));
+    let output = git(&["-C", &root.to_string_lossy(), "show", &object])?;
+    String::from_utf8(output).map_err(|_| format!("the staged {} isn't valid UTF-8", path.display()))
+}
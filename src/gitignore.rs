@@ -0,0 +1,188 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Skipping the files ignored by git or excluded with gitignore-style
+//! patterns.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+
+/// The project's ignore file, with gitignore syntax and relative to the
+/// project root.
+pub const IGNORE_FILE: &str = ".licensepreambleignore";
+
+/// Reads a gitignore-style file whose patterns are relative to the current
+/// directory, `None` if it doesn't exist.
+pub fn read_ignore_file(ignore_path: &Path) -> Option<Gitignore> {
+    if !ignore_path.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(".");
+    if let Some(err) = builder.add(ignore_path) {
+        eprintln!("Warning: couldn't read {}: {err}", ignore_path.display());
+    }
+    builder.build().ok()
+}
+
+/// The ignore files of each directory, read once.
+#[derive(Default)]
+pub struct IgnoreRules {
+    matchers: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    /// The rules of `dir`: its `.gitignore` and `.ignore` files and, at the
+    /// root of a repository, `.git/info/exclude`. Later files take
+    /// precedence, like with git and ripgrep.
+    fn matcher(&mut self, dir: &Path) -> Option<&Gitignore> {
+        self.matchers.entry(dir.to_path_buf()).or_insert_with(|| {
+            let mut builder = GitignoreBuilder::new(dir);
+            let mut found = false;
+            for ignore_path in [dir.join(".git/info/exclude"), dir.join(".gitignore"), dir.join(".ignore")] {
+                if ignore_path.is_file() {
+                    found = true;
+                    if let Some(err) = builder.add(&ignore_path) {
+                        eprintln!("Warning: couldn't read {}: {err}", ignore_path.display());
+                    }
+                }
+            }
+            found.then(|| builder.build().ok()).flatten()
+        }).as_ref()
+    }
+
+    /// Why `path` is ignored, naming the pattern and the file it's from, or
+    /// `None` if it isn't. Rules are read from the directories containing
+    /// the file up to the root of the repository, the nearest taking
+    /// precedence.
+    pub fn ignored_by(&mut self, path: &Path) -> Option<String> {
+        let path = std::path::absolute(path).ok()?;
+        for dir in path.ancestors().skip(1) {
+            if let Some(matcher) = self.matcher(dir) {
+                match matcher.matched_path_or_any_parents(&path, false) {
+                    Match::None => {}
+                    Match::Ignore(glob) => {
+                        let from = glob.from().map_or_else(|| dir.to_path_buf(), Path::to_path_buf);
+                        return Some(format!("`{}` in {}", glob.original(), from.display()));
+                    }
+                    Match::Whitelist(_) => return None,
+                }
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+}
+
+/// Gitignore-style patterns excluding paths under a source root, along with
+/// the project's ignore file, recording what they matched. Shared by the
+/// threads walking the root.
+pub struct Excludes {
+    matcher: Gitignore,
+    ignore_file: Option<Gitignore>,
+    /// The patterns that matched something.
+    used: Mutex<HashSet<String>>,
+    /// The excluded files and directories.
+    excluded: Mutex<Vec<PathBuf>>,
+}
+
+impl Excludes {
+    /// Exits with an error if a pattern is invalid.
+    pub fn new(root: &Path, patterns: &[String], ignore_file: Option<Gitignore>) -> Excludes {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            if let Err(err) = builder.add_line(None, pattern) {
+                eprintln!("Invalid exclude pattern `{pattern}`: {err}");
+                std::process::exit(1);
+            }
+        }
+        let matcher = builder.build().unwrap_or_else(|err| {
+            eprintln!("Invalid exclude patterns: {err}");
+            std::process::exit(1);
+        });
+        Excludes { matcher, ignore_file, used: Mutex::default(), excluded: Mutex::default() }
+    }
+
+    /// Whether `path` is excluded by a pattern or by the ignore file. Each
+    /// can exclude a path on its own, the ignore file's `!` patterns only
+    /// re-include paths it excluded itself.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if let Match::Ignore(glob) = self.matcher.matched(path, is_dir) {
+            self.used.lock().unwrap().insert(glob.original().to_string());
+        } else if !self.ignore_file.as_ref().is_some_and(|ignore_file| ignore_file.matched(path, is_dir).is_ignore()) {
+            return false;
+        }
+        self.excluded.lock().unwrap().push(path.to_path_buf());
+        true
+    }
+
+    /// The excluded files and directories so far, leaving none recorded.
+    pub fn take_excluded(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.excluded.lock().unwrap())
+    }
+
+    /// Whether `pattern` matched anything so far.
+    pub fn was_used(&self, pattern: &str) -> bool {
+        self.used.lock().unwrap().contains(pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads an ignore file holding `lines`.
+    fn ignore_file(name: &str, lines: &str) -> Option<Gitignore> {
+        let ignore_path = std::env::temp_dir().join(format!("license-preamble-{name}-{}", std::process::id()));
+        std::fs::write(&ignore_path, lines).unwrap();
+        let ignore_file = read_ignore_file(&ignore_path);
+        std::fs::remove_file(ignore_path).unwrap();
+        ignore_file
+    }
+
+    #[test]
+    fn negation_re_includes_a_file_under_an_excluded_directory() {
+        let ignore_file = ignore_file("negation", "src/vendor/*\n!src/vendor/keep.ts\n");
+        let excludes = Excludes::new(Path::new("src"), &[], ignore_file);
+        assert!(!excludes.is_excluded(Path::new("src/vendor/keep.ts"), false));
+        assert!(excludes.is_excluded(Path::new("src/vendor/other.ts"), false));
+        assert!(!excludes.is_excluded(Path::new("src/main.ts"), false));
+    }
+
+    #[test]
+    fn negation_before_the_pattern_it_negates_has_no_effect() {
+        let ignore_file = ignore_file("negation-order", "!src/vendor/keep.ts\nsrc/vendor/*\n");
+        let excludes = Excludes::new(Path::new("src"), &[], ignore_file);
+        assert!(excludes.is_excluded(Path::new("src/vendor/keep.ts"), false));
+    }
+
+    #[test]
+    fn negation_doesnt_re_include_paths_excluded_with_patterns() {
+        let ignore_file = ignore_file("negation-exclude", "!src/vendor/keep.ts\n");
+        let excludes = Excludes::new(Path::new("src"), &[String::from("vendor/")], ignore_file);
+        assert!(excludes.is_excluded(Path::new("src/vendor"), true));
+    }
+}
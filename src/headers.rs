@@ -0,0 +1,79 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The short per-file headers conventionally used instead of the full text
+//! of some licenses.
+
+const APACHE_HEADER: &str = r#"Copyright [year] [fullname]
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License."#;
+
+const MPL_HEADER: &str = "\
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.";
+
+/// The "This program is free software" notice of the GNU licenses.
+fn gnu_header(license: &str, version: &str) -> String {
+    format!(
+        "\
+Copyright (C) [year] [fullname]
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the {license} as published by
+the Free Software Foundation, either version {version} of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+{license} for more details.
+
+You should have received a copy of the {license}
+along with this program.  If not, see <https://www.gnu.org/licenses/>."
+    )
+}
+
+/// The standard header of a license, if it has one. Placeholders are left
+/// to be filled in like the license text.
+pub fn standard_header(spdx_id: &str) -> Option<String> {
+    match spdx_id {
+        "Apache-2.0" => Some(APACHE_HEADER.to_string()),
+        "MPL-2.0" => Some(MPL_HEADER.to_string()),
+        "GPL-2.0" => Some(gnu_header("GNU General Public License", "2")),
+        "GPL-3.0" => Some(gnu_header("GNU General Public License", "3")),
+        "LGPL-2.1" => Some(gnu_header("GNU Lesser General Public License", "2.1")),
+        "LGPL-3.0" => Some(gnu_header("GNU Lesser General Public License", "3")),
+        "AGPL-3.0" => Some(gnu_header("GNU Affero General Public License", "3")),
+        _ => None,
+    }
+}
@@ -0,0 +1,107 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The git pre-commit hook running `check` on staged files.
+
+use std::path::Path;
+
+/// Where the hook is installed, relative to the project root.
+pub const HOOK_PATH: &str = ".git/hooks/pre-commit";
+
+const BEGIN_MARKER: &str = "# >>> license-preamble >>>";
+const END_MARKER: &str = "# <<< license-preamble <<<";
+
+const SHEBANG: &str = "#!/bin/sh";
+
+/// Checks the staged contents of the staged files and blocks the commit when
+/// some are missing the preamble. Files without a supported extension are
+/// ignored by `check`.
+const SNIPPET: &str = "license-preamble check --staged || exit 1";
+/// Adds the snippet to the pre-commit hook, creating it if needed. Existing
+/// hooks are only appended to when they are shell scripts.
+pub fn hook_install(hook_path: &Path) -> bool {
+    let existing = std::fs::read_to_string(hook_path).ok();
+    let contents = match existing {
+        Some(existing) if existing.contains(BEGIN_MARKER) => {
+            println!("The hook is already installed in {}", hook_path.display());
+            return true;
+        }
+        Some(existing) => {
+            let is_shell = existing.lines().next().is_some_and(|shebang| {
+                ["#!/bin/sh", "#!/bin/bash", "#!/usr/bin/env sh", "#!/usr/bin/env bash"].contains(&shebang.trim())
+            });
+            if !is_shell {
+                eprintln!(
+                    "Refusing to modify {}, it isn't a shell script. Add `license-preamble check` to it by hand",
+                    hook_path.display()
+                );
+                return false;
+            }
+            format!("{}\n\n{BEGIN_MARKER}\n{SNIPPET}\n{END_MARKER}\n", existing.trim_end())
+        }
+        None => {
+            let Some(hooks_dir) = hook_path.parent().filter(|dir| dir.parent().is_some_and(Path::is_dir)) else {
+                eprintln!("Not a git repository, run this from the project root");
+                return false;
+            };
+            std::fs::create_dir_all(hooks_dir).unwrap();
+            format!("{SHEBANG}\n\n{BEGIN_MARKER}\n{SNIPPET}\n{END_MARKER}\n")
+        }
+    };
+
+    std::fs::write(hook_path, contents).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = std::fs::metadata(hook_path).unwrap().permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+        std::fs::set_permissions(hook_path, permissions).unwrap();
+    }
+    println!("Installed the hook in {}", hook_path.display());
+    true
+}
+
+/// Removes the lines added by `hook_install`, deleting the hook if nothing
+/// else is left in it.
+pub fn hook_uninstall(hook_path: &Path) -> bool {
+    let Ok(existing) = std::fs::read_to_string(hook_path) else {
+        eprintln!("No hook found in {}", hook_path.display());
+        return false;
+    };
+    let Some(begin) = existing.find(BEGIN_MARKER) else {
+        eprintln!("The hook isn't installed in {}", hook_path.display());
+        return false;
+    };
+    let Some(end) = existing[begin..].find(END_MARKER).map(|end| begin + end + END_MARKER.len()) else {
+        eprintln!("Refusing to modify {}, the end of the hook is missing", hook_path.display());
+        return false;
+    };
+
+    let remaining = format!("{}\n{}", existing[..begin].trim_end(), existing[end..].trim_start_matches('\n'));
+    if remaining.trim().is_empty() || remaining.trim() == SHEBANG {
+        std::fs::remove_file(hook_path).unwrap();
+    } else {
+        std::fs::write(hook_path, format!("{}\n", remaining.trim_end())).unwrap();
+    }
+    println!("Uninstalled the hook from {}", hook_path.display());
+    true
+}
@@ -0,0 +1,145 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Fuzzy matching of license texts against the embedded licenses.
+
+use std::collections::HashMap;
+use yaml_front_matter::Document;
+
+use crate::headers::standard_header;
+use crate::license::LicenseInfo;
+
+/// Minimum confidence for a text to be identified as one of the licenses.
+pub const CONFIDENCE_THRESHOLD: f64 = 0.9;
+
+/// How many of the closest licenses by vocabulary are compared word by word.
+const CANDIDATES: usize = 3;
+
+/// Splits a text into lowercase words, dropping punctuation and the
+/// `[placeholder]` spans of the embedded license templates.
+fn words(text: &str) -> Vec<String> {
+    let mut without_placeholders = String::with_capacity(text.len());
+    let mut in_placeholder = false;
+    for c in text.chars() {
+        match c {
+            '[' => in_placeholder = true,
+            ']' if in_placeholder => in_placeholder = false,
+            _ if in_placeholder => {}
+            _ => without_placeholders.push(c),
+        }
+    }
+
+    without_placeholders
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Dice coefficient of the two texts' word multisets, a cheap approximation
+/// of [`sequence_similarity`].
+fn vocabulary_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for word in a {
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut shared = 0;
+    for word in b {
+        if let Some(count) = counts.get_mut(word.as_str()).filter(|count| **count > 0) {
+            *count -= 1;
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// Dice coefficient of the longest common subsequence of words, so missing
+/// or reordered paragraphs lower the score.
+fn sequence_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+    for word in a {
+        for (j, other) in b.iter().enumerate() {
+            current[j + 1] = if word == other {
+                previous[j] + 1
+            } else {
+                current[j].max(previous[j + 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    2.0 * previous[b.len()] as f64 / (a.len() + b.len()) as f64
+}
+
+/// Finds the license closest to `text` along with the confidence of the
+/// match.
+pub fn identify<'a>(text: &str, licenses: &'a [Document<LicenseInfo>]) -> Option<(&'a Document<LicenseInfo>, f64)> {
+    let text_words = words(text);
+    let mut candidates: Vec<(&Document<LicenseInfo>, Vec<String>, f64)> = licenses.iter().map(|license| {
+        let license_words = words(&license.content);
+        let similarity = vocabulary_similarity(&text_words, &license_words);
+        (license, license_words, similarity)
+    }).collect();
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    candidates.into_iter()
+        .take(CANDIDATES)
+        .map(|(license, license_words, _)| (license, sequence_similarity(&text_words, &license_words)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Finds what a PREAMBLE is for, comparing it with the full text and the
+/// standard header of each license. The PREAMBLE of a dual license is
+/// recognized by the license expression of its `SPDX-License-Identifier`
+/// line.
+pub fn identify_preamble(text: &str, licenses: &[Document<LicenseInfo>]) -> Option<String> {
+    if let Some(expression) = text.lines().find_map(|line| line.trim().strip_prefix("SPDX-License-Identifier:")) {
+        let titles: Option<Vec<&str>> = expression.split_whitespace()
+            .map(|spdx_id| spdx_id.trim_matches(['(', ')']))
+            .filter(|spdx_id| !matches!(*spdx_id, "" | "OR" | "AND"))
+            .map(|spdx_id| {
+                let license = licenses.iter().find(|license| license.metadata.spdx_id == spdx_id)?;
+                Some(license.metadata.title.as_str())
+            })
+            .collect();
+        return titles.map(|titles| titles.join(" or the "));
+    }
+    if let Some((license, confidence)) = identify(text, licenses) {
+        if confidence >= CONFIDENCE_THRESHOLD {
+            return Some(license.metadata.title.clone());
+        }
+    }
+
+    let text_words = words(text);
+    licenses.iter()
+        .filter_map(|license| Some((license, words(&standard_header(&license.metadata.spdx_id)?))))
+        .map(|(license, header_words)| (license, sequence_similarity(&text_words, &header_words)))
+        .filter(|(_, confidence)| *confidence >= CONFIDENCE_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(license, _)| format!("{} header", license.metadata.title))
+}
@@ -0,0 +1,277 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The licenses embedded from choosealicense.com.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use yaml_front_matter::{Document, YamlFrontMatter};
+
+static LICENSE_FILES: [&str; 47] = [
+    include_str!("../licenses/0bsd.txt"),
+    include_str!("../licenses/afl-3.0.txt"),
+    include_str!("../licenses/agpl-3.0.txt"),
+    include_str!("../licenses/apache-2.0.txt"),
+    include_str!("../licenses/artistic-2.0.txt"),
+    include_str!("../licenses/blueoak-1.0.0.txt"),
+    include_str!("../licenses/bsd-2-clause.txt"),
+    include_str!("../licenses/bsd-2-clause-patent.txt"),
+    include_str!("../licenses/bsd-3-clause.txt"),
+    include_str!("../licenses/bsd-3-clause-clear.txt"),
+    include_str!("../licenses/bsd-4-clause.txt"),
+    include_str!("../licenses/bsl-1.0.txt"),
+    include_str!("../licenses/cc0-1.0.txt"),
+    include_str!("../licenses/cc-by-4.0.txt"),
+    include_str!("../licenses/cc-by-sa-4.0.txt"),
+    include_str!("../licenses/cecill-2.1.txt"),
+    include_str!("../licenses/cern-ohl-p-2.0.txt"),
+    include_str!("../licenses/cern-ohl-s-2.0.txt"),
+    include_str!("../licenses/cern-ohl-w-2.0.txt"),
+    include_str!("../licenses/ecl-2.0.txt"),
+    include_str!("../licenses/epl-1.0.txt"),
+    include_str!("../licenses/epl-2.0.txt"),
+    include_str!("../licenses/eupl-1.1.txt"),
+    include_str!("../licenses/eupl-1.2.txt"),
+    include_str!("../licenses/gfdl-1.3.txt"),
+    include_str!("../licenses/gpl-2.0.txt"),
+    include_str!("../licenses/gpl-3.0.txt"),
+    include_str!("../licenses/isc.txt"),
+    include_str!("../licenses/lgpl-2.1.txt"),
+    include_str!("../licenses/lgpl-3.0.txt"),
+    include_str!("../licenses/lppl-1.3c.txt"),
+    include_str!("../licenses/mit.txt"),
+    include_str!("../licenses/mit-0.txt"),
+    include_str!("../licenses/mpl-2.0.txt"),
+    include_str!("../licenses/ms-pl.txt"),
+    include_str!("../licenses/ms-rl.txt"),
+    include_str!("../licenses/mulanpsl-2.0.txt"),
+    include_str!("../licenses/ncsa.txt"),
+    include_str!("../licenses/odbl-1.0.txt"),
+    include_str!("../licenses/ofl-1.1.txt"),
+    include_str!("../licenses/osl-3.0.txt"),
+    include_str!("../licenses/postgresql.txt"),
+    include_str!("../licenses/unlicense.txt"),
+    include_str!("../licenses/upl-1.0.txt"),
+    include_str!("../licenses/vim.txt"),
+    include_str!("../licenses/wtfpl.txt"),
+    include_str!("../licenses/zlib.txt"),
+];
+
+#[allow(unused)]
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LicenseInfo {
+    pub title: String,
+    pub description: String,
+    pub how: String,
+    pub using: Option<BTreeMap<String, String>>,
+    pub permissions: Vec<String>,
+    pub conditions: Vec<String>,
+    pub limitations: Vec<String>,
+    pub featured: Option<bool>,
+    #[serde(rename = "spdx-id")]
+    pub spdx_id: String,
+}
+
+/// How strongly a license requires derivative works to keep it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Copyleft {
+    /// Permissive, derivative works may use any license.
+    None,
+    /// Only modified files or the library itself keep the license, e.g.
+    /// MPL-2.0 or LGPL-3.0.
+    Weak,
+    /// Derivative works keep the license, e.g. GPL-3.0.
+    Strong,
+    /// Like strong, and offering the software over a network counts as
+    /// distributing it, e.g. AGPL-3.0.
+    Network,
+}
+
+/// The conditions making a license copyleft, strongest first.
+static COPYLEFT_CONDITIONS: [(&str, Copyleft); 4] = [
+    ("network-use-disclose", Copyleft::Network),
+    ("same-license", Copyleft::Strong),
+    ("same-license--library", Copyleft::Weak),
+    ("same-license--file", Copyleft::Weak),
+];
+
+impl Copyleft {
+    pub fn of(info: &LicenseInfo) -> Copyleft {
+        COPYLEFT_CONDITIONS.iter()
+            .find(|(condition, _)| info.conditions.iter().any(|other| other == condition))
+            .map_or(Copyleft::None, |(_, copyleft)| *copyleft)
+    }
+}
+
+impl std::fmt::Display for Copyleft {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(match self {
+            Copyleft::None => "none",
+            Copyleft::Weak => "weak",
+            Copyleft::Strong => "strong",
+            Copyleft::Network => "network",
+        })
+    }
+}
+
+pub fn load_licenses() -> Vec<Document<LicenseInfo>> {
+    LICENSE_FILES.iter().map(|license| YamlFrontMatter::parse(license).unwrap()).collect()
+}
+
+/// Colloquial license names and the SPDX ids they may refer to. Names are
+/// matched after [`normalize_alias`], and names with several ids are
+/// ambiguous.
+static ALIASES: [(&str, &[&str]); 24] = [
+    ("gpl", &["GPL-2.0", "GPL-3.0"]),
+    ("gplv2", &["GPL-2.0"]),
+    ("gpl2", &["GPL-2.0"]),
+    ("gplv3", &["GPL-3.0"]),
+    ("gpl3", &["GPL-3.0"]),
+    ("lgpl", &["LGPL-2.1", "LGPL-3.0"]),
+    ("lgplv2", &["LGPL-2.1"]),
+    ("lgplv21", &["LGPL-2.1"]),
+    ("lgplv3", &["LGPL-3.0"]),
+    ("lgpl3", &["LGPL-3.0"]),
+    ("agpl", &["AGPL-3.0"]),
+    ("agplv3", &["AGPL-3.0"]),
+    ("agpl3", &["AGPL-3.0"]),
+    ("apache", &["Apache-2.0"]),
+    ("apache2", &["Apache-2.0"]),
+    ("apachev2", &["Apache-2.0"]),
+    ("bsd", &["BSD-2-Clause", "BSD-3-Clause", "BSD-4-Clause"]),
+    ("bsd2", &["BSD-2-Clause"]),
+    ("bsd3", &["BSD-3-Clause"]),
+    ("bsd4", &["BSD-4-Clause"]),
+    ("mpl", &["MPL-2.0"]),
+    ("mpl2", &["MPL-2.0"]),
+    ("epl", &["EPL-1.0", "EPL-2.0"]),
+    ("boost", &["BSL-1.0"]),
+];
+
+/// Lowercases a license name and drops everything but letters and digits,
+/// so `GPL v3`, `gpl-v3` and `GPLv3` are the same alias.
+fn normalize_alias(name: &str) -> String {
+    name.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+/// The SPDX ids an alias may refer to.
+fn alias_candidates(name: &str) -> &'static [&'static str] {
+    let name = normalize_alias(name);
+    ALIASES.iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(&[], |(_, spdx_ids)| *spdx_ids)
+}
+
+/// The unambiguous aliases of a license.
+pub fn aliases(spdx_id: &str) -> Vec<&'static str> {
+    ALIASES.iter()
+        .filter(|(_, spdx_ids)| *spdx_ids == [spdx_id])
+        .map(|(alias, _)| *alias)
+        .collect()
+}
+
+/// How many "did you mean" suggestions are given for an unknown license.
+const SUGGESTIONS: usize = 3;
+
+/// Finds a license by its title, SPDX id or unambiguous alias, ignoring
+/// case and surrounding whitespace.
+pub fn lookup_license<'a>(licenses: &'a [Document<LicenseInfo>], license: &str) -> Option<&'a Document<LicenseInfo>> {
+    let license = license.trim();
+    let spdx_id = match alias_candidates(license) {
+        [spdx_id] => Some(*spdx_id),
+        _ => None,
+    };
+    let normalized = normalize_alias(license);
+    licenses.iter().find(|document| {
+        let info = &document.metadata;
+        info.title.eq_ignore_ascii_case(license)
+            || info.spdx_id.eq_ignore_ascii_case(license)
+            || normalize_alias(&info.spdx_id) == normalized
+            || spdx_id == Some(info.spdx_id.as_str())
+    })
+}
+
+/// The SPDX ids of the licenses whose title or id are closest to `license`.
+fn suggestions<'a>(licenses: &'a [Document<LicenseInfo>], license: &str) -> Vec<&'a str> {
+    let license = license.trim().to_lowercase();
+    let mut scored: Vec<(usize, &str)> = licenses.iter().map(|document| {
+        let info = &document.metadata;
+        let distance = [&info.title, &info.spdx_id].iter()
+            .map(|name| strsim::levenshtein(&name.to_lowercase(), &license))
+            .min()
+            .unwrap();
+        (distance, info.spdx_id.as_str())
+    }).collect();
+    scored.sort();
+
+    let max_distance = (license.chars().count() / 2).max(2);
+    scored.into_iter()
+        .filter(|(distance, _)| *distance <= max_distance)
+        .take(SUGGESTIONS)
+        .map(|(_, spdx_id)| spdx_id)
+        .collect()
+}
+
+/// Reports an unknown license along with the closest matches, and exits.
+pub fn unknown_license(licenses: &[Document<LicenseInfo>], license: &str) -> ! {
+    let candidates = alias_candidates(license);
+    if candidates.len() > 1 {
+        eprintln!("`{}` is ambiguous, it could mean {}", license.trim(), candidates.join(", "));
+        std::process::exit(1);
+    }
+    eprintln!("Unknown license `{}`", license.trim());
+    let suggestions = suggestions(licenses, license);
+    if !suggestions.is_empty() {
+        eprintln!("Did you mean {}?", suggestions.join(", "));
+    }
+    eprintln!("List available licenses with `list`");
+    std::process::exit(1);
+}
+
+/// Like [`lookup_license`], exiting with an error when there's no such
+/// license.
+pub fn find_license<'a>(licenses: &'a [Document<LicenseInfo>], license: &str) -> &'a Document<LicenseInfo> {
+    lookup_license(licenses, license).unwrap_or_else(|| unknown_license(licenses, license))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copyleft_of(spdx_id: &str) -> Copyleft {
+        let licenses = load_licenses();
+        let license = licenses.iter()
+            .find(|license| license.metadata.spdx_id == spdx_id)
+            .unwrap_or_else(|| panic!("{spdx_id} isn't embedded"));
+        Copyleft::of(&license.metadata)
+    }
+
+    #[test]
+    fn copyleft_classifies_the_common_licenses() {
+        assert_eq!(copyleft_of("MIT"), Copyleft::None);
+        assert_eq!(copyleft_of("MPL-2.0"), Copyleft::Weak);
+        assert_eq!(copyleft_of("LGPL-3.0"), Copyleft::Weak);
+        assert_eq!(copyleft_of("GPL-3.0"), Copyleft::Strong);
+        assert_eq!(copyleft_of("AGPL-3.0"), Copyleft::Network);
+    }
+}
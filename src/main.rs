@@ -31,65 +31,152 @@
 //!
 //! MIT license
 
-use std::collections::HashMap;
-use std::path::Path;
-use clap::{Parser, Subcommand};
-use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
+use serde::Serialize;
 use yaml_front_matter::Document;
 
-static LICENSE_FILES: [&str; 47] = [
-    include_str!("../licenses/0bsd.txt"),
-    include_str!("../licenses/afl-3.0.txt"),
-    include_str!("../licenses/agpl-3.0.txt"),
-    include_str!("../licenses/apache-2.0.txt"),
-    include_str!("../licenses/artistic-2.0.txt"),
-    include_str!("../licenses/blueoak-1.0.0.txt"),
-    include_str!("../licenses/bsd-2-clause.txt"),
-    include_str!("../licenses/bsd-2-clause-patent.txt"),
-    include_str!("../licenses/bsd-3-clause.txt"),
-    include_str!("../licenses/bsd-3-clause-clear.txt"),
-    include_str!("../licenses/bsd-4-clause.txt"),
-    include_str!("../licenses/bsl-1.0.txt"),
-    include_str!("../licenses/cc0-1.0.txt"),
-    include_str!("../licenses/cc-by-4.0.txt"),
-    include_str!("../licenses/cc-by-sa-4.0.txt"),
-    include_str!("../licenses/cecill-2.1.txt"),
-    include_str!("../licenses/cern-ohl-p-2.0.txt"),
-    include_str!("../licenses/cern-ohl-s-2.0.txt"),
-    include_str!("../licenses/cern-ohl-w-2.0.txt"),
-    include_str!("../licenses/ecl-2.0.txt"),
-    include_str!("../licenses/epl-1.0.txt"),
-    include_str!("../licenses/epl-2.0.txt"),
-    include_str!("../licenses/eupl-1.1.txt"),
-    include_str!("../licenses/eupl-1.2.txt"),
-    include_str!("../licenses/gfdl-1.3.txt"),
-    include_str!("../licenses/gpl-2.0.txt"),
-    include_str!("../licenses/gpl-3.0.txt"),
-    include_str!("../licenses/isc.txt"),
-    include_str!("../licenses/lgpl-2.1.txt"),
-    include_str!("../licenses/lgpl-3.0.txt"),
-    include_str!("../licenses/lppl-1.3c.txt"),
-    include_str!("../licenses/mit.txt"),
-    include_str!("../licenses/mit-0.txt"),
-    include_str!("../licenses/mpl-2.0.txt"),
-    include_str!("../licenses/ms-pl.txt"),
-    include_str!("../licenses/ms-rl.txt"),
-    include_str!("../licenses/mulanpsl-2.0.txt"),
-    include_str!("../licenses/ncsa.txt"),
-    include_str!("../licenses/odbl-1.0.txt"),
-    include_str!("../licenses/ofl-1.1.txt"),
-    include_str!("../licenses/osl-3.0.txt"),
-    include_str!("../licenses/postgresql.txt"),
-    include_str!("../licenses/unlicense.txt"),
-    include_str!("../licenses/upl-1.0.txt"),
-    include_str!("../licenses/vim.txt"),
-    include_str!("../licenses/wtfpl.txt"),
-    include_str!("../licenses/zlib.txt"),
+use adopt::{adopt_license_file, find_license_file};
+use backup::{undo, Backup, BACKUPS_DIR};
+use bump_year::{bump_year, current_year};
+use completions::{print_completions, LICENSE_VALUE_NAME};
+use config::{config_init, Config, CONFIG_PATH};
+use diff::diff;
+use doctor::doctor;
+use filters::RuleFilters;
+use generated::{has_ignore_directive, is_generated, looks_minified};
+use git::{changed_files, staged_contents, staged_files, tracked_files};
+use gitignore::{read_ignore_file, Excludes, IgnoreRules, IGNORE_FILE};
+use headers::standard_header;
+use hook::{hook_install, hook_uninstall, HOOK_PATH};
+use identify::{identify, CONFIDENCE_THRESHOLD};
+use license::{aliases, find_license, Copyleft, load_licenses, lookup_license, unknown_license, LicenseInfo};
+use man::write_man_pages;
+use manifest::declared_license;
+use migrate::migrate;
+use normalize::normalize;
+use notice::notice;
+use pick::{ask_with_default, pick_license};
+use placeholders::{git_config, Placeholders};
+use preamble::{
+    contains_preamble, has_spdx_header, insert_header, join_prologue, line_ending, looks_like_notice, prefix_preamble,
+    presence, similar_leading_comment, spdx_header, split_leading_comment, split_prologue, starts_with_inline_html,
+    remove_header, strip_line_ending, strip_preamble, Presence,
+};
+use reuse::{reuse, reuse_check};
+use scopes::Preambles;
+use search::search;
+use status::status;
+use style::CommentStyle;
+use suggest::{ask_yes_no, Criteria, Kind};
+use table::{print_table, terminal_width, truncate};
+use template::{parse_variable, render_preamble};
+use verify::verify_license;
+use watch::watch;
+
+mod adopt;
+mod backup;
+mod bump_year;
+mod completions;
+mod config;
+mod diff;
+mod doctor;
+mod filters;
+mod generated;
+mod git;
+mod gitignore;
+mod headers;
+mod hook;
+mod identify;
+mod license;
+mod man;
+mod manifest;
+mod markers;
+mod migrate;
+mod normalize;
+mod notice;
+mod pick;
+mod placeholders;
+mod preamble;
+mod reuse;
+mod scopes;
+mod search;
+mod status;
+mod style;
+mod suggest;
+mod table;
+mod template;
+mod verify;
+mod watch;
+
+const EXAMPLES: &str = "\
+Examples:
+  license-preamble init MIT
+  license-preamble add
+  license-preamble check src lib";
+
+/// The comment syntax of each built-in extension. Block comments are
+/// written as the opening and closing tokens separated by a space, with the
+/// token put in front of every line in between if there's one, as in
+/// `/* * */`.
+static EXTENSIONS: [(&str, &str); 16] = [
+    ("rs", "//"),
+    ("swift", "//"),
+    ("js", "//"),
+    ("ts", "//"),
+    ("tsx", "//"),
+    ("jsx", "//"),
+    ("xml", "<!-- -->"),
+    ("svg", "<!-- -->"),
+    ("html", "<!-- -->"),
+    ("htm", "<!-- -->"),
+    ("vue", "<!-- -->"),
+    ("svelte", "<!-- -->"),
+    ("md", "<!-- -->"),
+    ("mdx", "<!-- -->"),
+    ("php", "//"),
+    ("css", "/* * */"),
+];
+
+/// Files matched by their whole name, for the ones without a useful
+/// extension.
+static FILENAMES: [(&str, &str); 5] = [
+    ("Makefile", "#"),
+    ("Dockerfile", "#"),
+    ("Justfile", "#"),
+    ("Rakefile", "#"),
+    ("CMakeLists.txt", "#"),
 ];
 
+/// Directories holding dependencies or build output, which are never
+/// walked unless `--no-default-excludes` is given.
+static DEFAULT_EXCLUDES: [&str; 8] = ["node_modules", "target", "vendor", ".git", "dist", "build", ".venv", "__pycache__"];
+
+/// Adds a license pre-amble to source files
+///
+/// `init` writes the LICENSE file for one of the embedded licenses from
+/// choosealicense.com and links PREAMBLE to it. `add` then prefixes every
+/// source file under the source roots with PREAMBLE, commented out with the
+/// file type's comment syntax. Other subcommands check, update, remove or
+/// migrate those preambles and help picking a license.
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, after_long_help = EXAMPLES)]
 struct Cli {
+    /// The project's root directory. By default it's the nearest directory
+    /// above the current one with a PREAMBLE, LICENSE or configuration file,
+    /// so that commands work from subdirectories. Relative paths are taken
+    /// from it
+    #[arg(long, global = true, value_hint = ValueHint::DirPath)]
+    project_root: Option<PathBuf>,
+    /// Write the preamble with line or block comments in languages with
+    /// both, instead of the style set under [styles] in the configuration
+    /// file
+    #[arg(long, global = true, value_enum)]
+    style: Option<CommentStyle>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -97,116 +184,2768 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize LICENSE and PREAMBLE files
+    ///
+    /// LICENSE is written with the license text. PREAMBLE gets the license's
+    /// standard short header, such as Apache's or the GNU licenses' notice,
+    /// or a copy of the text for licenses without one. With several licenses, each is written to its own
+    /// `LICENSE-<NAME>` file and PREAMBLE lets users pick one of them.
+    /// Existing files are only overwritten with `--force`. When the project
+    /// already has its license in a file such as LICENSE.md or COPYING,
+    /// only PREAMBLE is written for it, after asking when run interactively.
+    ///
+    /// Files left by an earlier run are completed rather than replaced: an
+    /// existing LICENSE gets a PREAMBLE, a missing LICENSE is recreated for
+    /// an existing PREAMBLE and a PREAMBLE symlink to a missing file is
+    /// replaced. The exit status tells what was done: 0 when both files
+    /// were written, 3 when they already existed, 4 when an existing
+    /// license file was adopted, 5 when LICENSE was recreated and 6 when a
+    /// dangling PREAMBLE symlink was repaired.
+    #[command(after_long_help = "Examples:\n  license-preamble init MIT\n  license-preamble init \"MIT OR Apache-2.0\"\n  license-preamble init Apache-2.0 --notice --holder \"ACME Corp\" --year 2024\n  license-preamble init --scope .=MIT --scope docs=CC-BY-4.0")]
     Init {
+        /// The license's SPDX id or title, see `list`. Several licenses,
+        /// given separately or as `MIT OR Apache-2.0`, make a dual license.
+        /// Taken from Cargo.toml or package.json, or asked for
+        /// interactively, when omitted
+        #[arg(value_name = LICENSE_VALUE_NAME)]
+        licenses: Vec<String>,
+        /// The copyright holder filling in `[fullname]`, git's `user.name`
+        /// by default
+        #[arg(long, visible_alias = "author")]
+        fullname: Option<String>,
+        /// The copyright year filling in `[year]` and named in NOTICE, the
+        /// current year by default
+        #[arg(long)]
+        year: Option<u16>,
+        /// The email address filling in `[email]`, git's `user.email` by
+        /// default
+        #[arg(long)]
+        email: Option<String>,
+        /// The project name filling in `[project]`
+        #[arg(long)]
+        project: Option<String>,
+        /// Don't read the defaults from git's configuration
+        #[arg(long)]
+        no_git: bool,
+        /// Use the license text in this file instead of an embedded license
+        #[arg(long, value_hint = ValueHint::FilePath, conflicts_with = "licenses")]
+        license_file: Option<PathBuf>,
+        /// The directory to initialize, the current one by default
+        #[arg(long, visible_alias = "output-dir", value_hint = ValueHint::DirPath)]
+        dir: Option<PathBuf>,
+        /// Create the directory if it doesn't exist
+        #[arg(long, requires = "dir")]
+        create_dir: bool,
+        /// Initialize DIR with LICENSE instead of the current directory.
+        /// Can be repeated to give parts of a monorepo their own license
+        #[arg(
+            long = "scope",
+            value_name = "DIR=LICENSE",
+            value_parser = parse_scope,
+            conflicts_with_all = ["licenses", "license_file", "dir", "notice"],
+        )]
+        scopes: Vec<(PathBuf, String)>,
+        /// Create the scope directories that don't exist
+        #[arg(long, requires = "scopes")]
+        create_dirs: bool,
+        /// Write LICENSE even if the license is already in a file such as
+        /// LICENSE.md or COPYING, instead of writing only PREAMBLE for it
+        #[arg(long)]
+        no_adopt: bool,
+        /// Replace existing LICENSE and PREAMBLE files
+        #[arg(long)]
+        force: bool,
+        /// What PREAMBLE contains
+        #[arg(long, value_enum, default_value_t = PreambleLayout::Header)]
+        preamble: PreambleLayout,
+        /// Also write a NOTICE file, see `notice`
+        #[arg(long, requires = "holder")]
+        notice: bool,
+        /// The copyright holder named in NOTICE
+        #[arg(long, requires = "notice")]
+        holder: Option<String>,
+    },
+    /// List available licenses
+    ///
+    /// Prints the title, SPDX id and copyleft strength of every embedded
+    /// license. The title or the id can be passed wherever a license name
+    /// is expected, as can common aliases such as `GPLv3` or `Apache2`.
+    ///
+    /// The `--permits`, `--requires` and `--forbids` filters and their
+    /// `--not-` forms match the rules shown by `info`, and a license must
+    /// pass all of them.
+    ///
+    /// The featured licenses, the most common choices, are listed first.
+    #[command(after_long_help = "Examples:\n  license-preamble list\n  license-preamble list | grep -i gpl\n  license-preamble list --json\n  license-preamble list --ids | grep -qx MIT\n  license-preamble list --table --columns id,copyleft,featured\n  license-preamble list --permits patent-use --not-requires disclose-source")]
+    List {
+        /// Also show each license's aliases, the first sentence of its
+        /// description, its permissions, conditions and limitations and the
+        /// projects using it
+        #[arg(long)]
+        verbose: bool,
+        /// The output format
+        #[arg(long, value_enum, default_value_t = ListFormat::Plain)]
+        format: ListFormat,
+        /// Shorthand for `--format json`
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// The order licenses are listed in
+        #[arg(long, value_enum, default_value_t = ListSort::Featured)]
+        sort: ListSort,
+        /// Only list the featured licenses
+        #[arg(long)]
+        featured_only: bool,
+        /// Don't add the first sentence of the descriptions, which is done
+        /// when the terminal is wide enough
+        #[arg(long)]
+        no_description: bool,
+        /// Only print the SPDX ids, one per line
+        #[arg(long, visible_alias = "quiet", conflicts_with_all = ["verbose", "format", "json"])]
+        ids: bool,
+        /// Print a table fitting the terminal, or tab-separated values when
+        /// stdout isn't a terminal
+        #[arg(long, conflicts_with_all = ["verbose", "format", "json", "ids"])]
+        table: bool,
+        /// The table's columns
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_values_t = [ListColumn::Id, ListColumn::Title, ListColumn::Copyleft],
+            requires = "table",
+        )]
+        columns: Vec<ListColumn>,
+        #[command(flatten)]
+        filters: RuleFilters,
+    },
+    /// Search the licenses' titles, descriptions and rules
+    ///
+    /// Matching is case-insensitive and a license must contain every term.
+    /// Exits with a non-zero status when nothing matches.
+    Search {
+        /// The terms to search for
+        #[arg(required = true)]
+        terms: Vec<String>,
+        /// Print the matches as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the supported file extensions and names and their comment syntax
+    ///
+    /// Extensions that aren't in the built-in table, or whose comment syntax
+    /// was changed, are marked as coming from the configuration file, and so
+    /// are the file names added under `filenames`. File names are matched
+    /// when the extension isn't, e.g. for `Makefile` or `CMakeLists.txt`.
+    Languages {
+        /// Show how a specific file would be treated instead
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        check: Option<PathBuf>,
+    },
+    /// Print the metadata and text of every license
+    Export {
+        /// The output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Only export this license
+        #[arg(long, value_name = LICENSE_VALUE_NAME)]
+        license: Option<String>,
+    },
+    /// Print the text of a license
+    Show {
         /// The license name
+        #[arg(value_name = LICENSE_VALUE_NAME)]
         license: String,
+        /// Also print the license metadata
+        #[arg(long)]
+        metadata: bool,
+    },
+    /// Show a summary of what a license permits and requires
+    ///
+    /// Also lists notable projects using the license, when known.
+    Info {
+        /// The license name
+        #[arg(value_name = LICENSE_VALUE_NAME)]
+        license: String,
+    },
+    /// Compare the permissions, conditions and limitations of licenses
+    ///
+    /// Rules that aren't shared by every license are marked with `*`.
+    Compare {
+        /// The license names
+        #[arg(num_args = 2.., required = true, value_name = LICENSE_VALUE_NAME)]
+        licenses: Vec<String>,
+    },
+    /// Identify which license a file contains
+    ///
+    /// Exits with a non-zero status when no license matches confidently.
+    Identify {
+        /// The license file
+        #[arg(default_value = "LICENSE")]
+        path: PathBuf,
+    },
+    /// Check that LICENSE hasn't drifted from the canonical license text
+    ///
+    /// The license is taken from the configuration file, or is the closest
+    /// match otherwise. Lines with placeholders such as the copyright line
+    /// may be filled in. Exits with a non-zero status when LICENSE differs.
+    VerifyLicense {
+        /// Rewrite LICENSE with the canonical text, keeping the filled-in
+        /// placeholder lines
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Check that the project is set up correctly
+    ///
+    /// Validates LICENSE and PREAMBLE and samples a few source files,
+    /// suggesting a fix for every problem found.
+    Doctor {
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Write a NOTICE file with the attribution for Apache-2.0 projects
+    Notice {
+        /// The copyright holder
+        #[arg(long)]
+        holder: String,
+        /// The copyright year
+        #[arg(long)]
+        year: u16,
+    },
+    /// Manage the project configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Manage the git pre-commit hook running `check` on staged files
+    Hook {
+        #[command(subcommand)]
+        command: HookCommands,
+    },
+    /// Write man pages for every subcommand
+    #[command(hide = true)]
+    Man {
+        /// The directory the pages are written to
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        out_dir: PathBuf,
+    },
+    /// Print a shell completion script
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Recommend a license by answering a few questions
+    ///
+    /// Questions are asked on stdin unless any of the flags is given, in
+    /// which case unspecified criteria are ignored.
+    Suggest {
+        /// Require derivative works to use the same license
+        #[arg(long, conflicts_with = "permissive")]
+        copyleft: bool,
+        /// Don't require derivative works to use the same license
+        #[arg(long)]
+        permissive: bool,
+        /// Require an explicit patent grant
+        #[arg(long)]
+        patent_grant: bool,
+        /// Require the license to be OSI approved
+        #[arg(long)]
+        osi_approved: bool,
+        /// What is being licensed
+        #[arg(long, value_enum)]
+        kind: Option<Kind>,
     },
-    /// List available licenses
-    List,
     /// Add the preamble to files
+    ///
+    /// Files that already start with the current preamble are skipped. Only
+    /// files with a known extension are modified, using the configured
+    /// comment syntax. Files ignored by git are skipped too, and so are
+    /// dependency and build directories such as `node_modules`, `target` or
+    /// the configured `exclude-dirs` and the paths listed in
+    /// `.licensepreambleignore`. Directories of third-party code, the
+    /// configured `vendored-dirs` such as `third_party`, are skipped as well.
+    ///
+    /// Each file gets the PREAMBLE in its nearest directory, so that the
+    /// packages of a monorepo can have their own, e.g. written with `init
+    /// --dir`.
+    ///
+    /// PREAMBLE may use `{{year}}`, `{{project}}` and `{{holder}}`, which are
+    /// the current year, the package name from Cargo.toml or package.json and
+    /// the configured `holder` or git's `user.name`, as well as variables set
+    /// under [variables] in the configuration file or with `--var`.
+    ///
+    /// Generated files, with a marker such as `@generated` or `DO NOT EDIT`
+    /// in their first lines, are skipped unless `skip-generated` is turned
+    /// off in the configuration file. More markers can be listed under
+    /// `generated-markers`. So are minified and bundled files such as
+    /// `*.min.js`, unless `--force-minified` is passed.
+    ///
+    /// A file is left alone by `add`, `check`, `update` and `remove` when one
+    /// of its first lines has a `license-preamble: ignore` comment, e.g. `//
+    /// license-preamble: ignore`.
+    ///
+    /// Files are processed in path order, so the output is the same from
+    /// one run to the next.
+    #[command(after_long_help = "Examples:\n  license-preamble add\n  license-preamble add --backup src tests\n  license-preamble add --exclude vendor --exclude \"*.pb.rs\"")]
     Add {
+        /// Save the original contents so the run can be reverted with `undo`
+        #[arg(long)]
+        backup: bool,
+        /// Only print the summary, not a line per file
+        #[arg(long)]
+        stats_only: bool,
+        /// Insert a one-line SPDX-License-Identifier header instead of the
+        /// preamble, skipping files that already have one
+        #[arg(long)]
+        spdx: bool,
+        /// The license of the SPDX header, the project's license by default
+        #[arg(long, requires = "spdx", value_name = LICENSE_VALUE_NAME)]
+        license: Option<String>,
+        /// Also add a `Copyright (c) <COPYRIGHT>` line after the SPDX header
+        #[arg(long, requires = "spdx")]
+        copyright: Option<String>,
+        /// Add the preamble even if it's longer than the configured
+        /// `max-preamble-lines`
+        #[arg(long, visible_alias = "yes")]
+        allow_long_preamble: bool,
+        /// Fill in `{{NAME}}` in PREAMBLE with VALUE, taking precedence over
+        /// the configured and built-in variables
+        #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+        /// Read the files to process from stdin, one per line, instead of
+        /// walking the source roots. Passing `-` as the source root does
+        /// the same
+        #[arg(long)]
+        stdin: bool,
+        /// Separate the paths read from stdin with NUL bytes instead of
+        /// newlines
+        #[arg(long)]
+        null: bool,
+        #[command(flatten)]
+        walk: WalkArgs,
+        /// Only process the files tracked by git
+        #[arg(long)]
+        tracked_only: bool,
+        /// Only process the files added or modified since this git ref,
+        /// e.g. `origin/main`
+        #[arg(long, value_name = "REF")]
+        changed_since: Option<String>,
+        /// Only process the files staged in git. The working tree is
+        /// modified, and the files need to be staged again
+        #[arg(long, conflicts_with = "stdin")]
+        staged: bool,
+        /// Also add the preamble to files that look minified or bundled,
+        /// such as `*.min.js` or files with very long lines
+        #[arg(long)]
+        force_minified: bool,
+        /// Replace the copyright or license header files already start
+        /// with, instead of skipping them
+        #[arg(long)]
+        replace_existing: bool,
+        /// Only take files as having the preamble when their copyright years
+        /// match PREAMBLE's, instead of leaving older years for `update`
+        #[arg(long)]
+        strict_year: bool,
+        /// Skip the files larger than this many bytes, the configured
+        /// `max-file-size` (4 MiB) by default. 0 disables the limit
+        #[arg(long, value_name = "BYTES")]
+        max_file_size: Option<u64>,
+        /// Read the files that aren't valid UTF-8 as Latin-1 instead of
+        /// skipping them. They're written back as UTF-8
+        #[arg(long)]
+        lossy: bool,
+        /// Print why files are skipped
+        #[arg(long, conflicts_with = "stats_only")]
+        verbose: bool,
+        /// The files to process and the directories to search for source
+        /// files, the configured roots by default. Glob patterns such as
+        /// `packages/*/src` are expanded
+        #[arg(value_hint = ValueHint::AnyPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Show the changes `add` would make as a unified diff
+    ///
+    /// Nothing is written. The output can be applied with `patch -p0`.
+    Diff {
+        /// Only list the files that would change
+        #[arg(long)]
+        name_only: bool,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Watch for new or saved files and add the preamble to them
+    ///
+    /// Runs until interrupted with Ctrl-C, then lists the files that were
+    /// modified.
+    Watch {
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
         source_root: Option<Vec<String>>,
     },
+    /// Add copyright and licensing information following the REUSE
+    /// specification
+    ///
+    /// Files that can contain comments get `SPDX-FileCopyrightText` and
+    /// `SPDX-License-Identifier` lines, other files such as images or JSON
+    /// get a `<file>.license` companion file with the same lines.
+    Reuse {
+        /// Only check that every file has the information, exiting with a
+        /// non-zero status otherwise
+        #[arg(long, conflicts_with_all = ["license", "copyright"])]
+        check: bool,
+        /// The license, the project's license by default
+        #[arg(long, value_name = LICENSE_VALUE_NAME)]
+        license: Option<String>,
+        /// The copyright notice, e.g. "2024 ACME Corp"
+        #[arg(long, required_unless_present = "check")]
+        copyright: Option<String>,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Check that files contain the preamble, without modifying them
+    ///
+    /// Files missing the current preamble are printed one per line and the
+    /// command exits with a non-zero status. Like with `add`, each file is
+    /// checked against the PREAMBLE in its nearest directory. Generated
+    /// and minified files are skipped like with `add`.
+    Check {
+        /// Read the files to process from stdin, one per line, instead of
+        /// walking the source roots. Passing `-` as the source root does
+        /// the same
+        #[arg(long)]
+        stdin: bool,
+        /// Separate the paths read from stdin with NUL bytes instead of
+        /// newlines
+        #[arg(long)]
+        null: bool,
+        #[command(flatten)]
+        walk: WalkArgs,
+        /// Fail for files whose copyright years don't match PREAMBLE's,
+        /// which are otherwise taken as having the preamble
+        #[arg(long)]
+        strict_year: bool,
+        /// Skip the files larger than this many bytes, the configured
+        /// `max-file-size` (4 MiB) by default. 0 disables the limit
+        #[arg(long, value_name = "BYTES")]
+        max_file_size: Option<u64>,
+        /// Only check the files tracked by git
+        #[arg(long)]
+        tracked_only: bool,
+        /// Only check the files added or modified since this git ref, e.g.
+        /// `origin/main`
+        #[arg(long, value_name = "REF")]
+        changed_since: Option<String>,
+        /// Only check the files staged in git, reading their staged
+        /// contents, as a pre-commit hook does
+        #[arg(long, conflicts_with = "stdin")]
+        staged: bool,
+        /// The files to process and the directories to search for source
+        /// files, the configured roots by default. Glob patterns such as
+        /// `packages/*/src` are expanded
+        #[arg(value_hint = ValueHint::AnyPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Revert the most recent `add --backup` run
+    ///
+    /// Refuses to restore anything if some of the files changed since the
+    /// preamble was added.
+    Undo {
+        /// Restore files even if they changed since the preamble was added
+        #[arg(long)]
+        force: bool,
+    },
+    /// Summarize which files have the preamble, without modifying them
+    ///
+    /// Files are counted as having the preamble, missing it, or having a
+    /// different header such as another copyright notice.
+    Status {
+        /// List the files in each category
+        #[arg(long)]
+        files: bool,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Remove the preamble from files
+    Remove {
+        #[command(flatten)]
+        walk: WalkArgs,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Switch LICENSE, PREAMBLE and every file's preamble to another license
+    ///
+    /// Refuses to change anything if the old preamble can't be found in some
+    /// of the files. PREAMBLE gets the new license's standard header, or a
+    /// copy of its text for licenses without one, like with `init`.
+    Migrate {
+        /// The new license name
+        #[arg(long, value_name = LICENSE_VALUE_NAME)]
+        to: String,
+        /// The copyright holder filling in `[fullname]`, the configured
+        /// `holder` or git's `user.name` by default
+        #[arg(long, visible_alias = "author")]
+        fullname: Option<String>,
+        /// Migrate even if the new preamble is longer than the configured
+        /// `max-preamble-lines`
+        #[arg(long)]
+        allow_long_preamble: bool,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Rewrite hand-written preambles to exactly what `add` would insert
+    ///
+    /// Fixes inconsistent spacing after the comment token, trailing
+    /// whitespace and CRLF line endings in the preamble, leaving the rest of
+    /// the file alone.
+    Normalize {
+        /// Print the changed lines of each file
+        #[arg(long)]
+        verbose: bool,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Update the copyright years in LICENSE, PREAMBLE and file preambles
+    ///
+    /// Lines such as `Copyright (c) 2021 ...` or `Copyright (c) 2019-2023
+    /// ...` are changed to a range ending at the target year. Only the
+    /// comment block at the top of each source file is touched.
+    BumpYear {
+        /// The target year, the current year by default
+        #[arg(long)]
+        year: Option<u16>,
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+    /// Replace outdated preambles with the current PREAMBLE contents
+    ///
+    /// A comment block at the top of a file is considered an outdated preamble
+    /// when its text is similar enough to the current one, so changes such as
+    /// a copyright year bump are picked up.
+    Update {
+        /// The directories to search for source files, the configured
+        /// roots by default
+        #[arg(value_hint = ValueHint::DirPath)]
+        source_root: Option<Vec<String>>,
+    },
+}
+
+/// `init`'s exit status when LICENSE and PREAMBLE already exist.
+const EXIT_ALREADY_INITIALIZED: i32 = 3;
+/// `init`'s exit status when PREAMBLE was written for an existing license
+/// file.
+const EXIT_ADOPTED: i32 = 4;
+/// `init`'s exit status when LICENSE was written for an existing PREAMBLE.
+const EXIT_RECREATED_LICENSE: i32 = 5;
+/// `init`'s exit status when a dangling PREAMBLE symlink was replaced.
+const EXIT_REPAIRED: i32 = 6;
+
+/// Whether PREAMBLE exists, as far as a previous `init` got.
+#[derive(Clone, Copy, PartialEq)]
+enum PreambleState {
+    Missing,
+    /// A symlink to a file that doesn't exist.
+    Dangling,
+    Present,
+}
+
+impl PreambleState {
+    fn of(preamble_path: &Path) -> PreambleState {
+        if preamble_path.exists() {
+            PreambleState::Present
+        } else if preamble_path.symlink_metadata().is_ok() {
+            PreambleState::Dangling
+        } else {
+            PreambleState::Missing
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PreambleLayout {
+    /// The license's standard short header, or a copy of the full text for
+    /// licenses without one
+    Header,
+    /// A copy of the full license text
+    #[value(alias = "copy")]
+    Full,
+    /// A symlink to LICENSE
+    Symlink,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ListFormat {
+    /// Aligned columns for reading
+    Plain,
+    /// An array of objects
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ListSort {
+    /// Featured licenses first, then by SPDX id
+    Featured,
+    /// By title
+    Title,
+    /// By SPDX id
+    Id,
 }
 
-#[allow(unused)]
-#[derive(Deserialize, Debug)]
-struct LicenseInfo {
-    title: String,
-    description: String,
-    how: String,
-    using: Option<HashMap<String, String>>,
-    permissions: Vec<String>,
-    conditions: Vec<String>,
-    limitations: Vec<String>,
-    featured: Option<bool>,
-    #[serde(rename = "spdx-id")]
-    spdx_id: String,
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ListColumn {
+    Id,
+    Title,
+    Copyleft,
+    Featured,
+}
+
+impl ListColumn {
+    fn header(self) -> &'static str {
+        match self {
+            ListColumn::Id => "ID",
+            ListColumn::Title => "TITLE",
+            ListColumn::Copyleft => "COPYLEFT",
+            ListColumn::Featured => "FEATURED",
+        }
+    }
+
+    fn cell(self, info: &LicenseInfo) -> String {
+        match self {
+            ListColumn::Id => info.spdx_id.clone(),
+            ListColumn::Title => info.title.clone(),
+            ListColumn::Copyleft => Copyleft::of(info).to_string(),
+            ListColumn::Featured => if info.featured.unwrap_or(false) { "yes" } else { "no" }.to_string(),
+        }
+    }
+}
+
+/// The width of a `list` line without the description.
+const LIST_LINE_WIDTH: usize = 60 + 12 + 20 + 14 + 7;
+/// Descriptions are only listed when at least this many characters fit.
+const MIN_DESCRIPTION_WIDTH: usize = 30;
+
+/// A license as printed by `list --format json`.
+#[derive(Serialize)]
+struct ListedLicense<'a> {
+    title: &'a str,
+    spdx_id: &'a str,
+    featured: bool,
+    copyleft: Copyleft,
+    permissions: &'a [String],
+    conditions: &'a [String],
+    limitations: &'a [String],
+}
+
+/// A license as printed by `export`.
+#[derive(Serialize)]
+struct ExportedLicense<'a> {
+    #[serde(flatten)]
+    info: &'a LicenseInfo,
+    body: &'a str,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Write the configuration file with the default settings
+    Init {
+        /// Overwrite an existing configuration file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommands {
+    /// Add the check to .git/hooks/pre-commit
+    Install,
+    /// Remove the check from .git/hooks/pre-commit
+    Uninstall,
+}
+
+/// Walks the source roots and returns every supported file along with its
+/// comment syntax. The configured source roots are used unless some are
+/// given.
+fn source_files(config: &Config, source_root: Option<Vec<String>>) -> Vec<(PathBuf, &str)> {
+    scan_source_roots(config, source_root, &WalkOptions::default()).files
+}
+
+/// The arguments choosing which files are walked, shared by the commands
+/// that modify or check many files.
+#[derive(Args)]
+struct WalkArgs {
+    /// Also process the files ignored by `.gitignore`, `.ignore` or
+    /// `.git/info/exclude`
+    #[arg(long)]
+    no_ignore: bool,
+    /// Also walk dependency and build directories such as `node_modules` and
+    /// `target`
+    #[arg(long)]
+    no_default_excludes: bool,
+    /// Skip the files and directories matching this gitignore-style pattern,
+    /// relative to the source root. Can be repeated
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Only process the files matching this glob, relative to the current
+    /// directory, e.g. `src/audio/**`. Can be repeated, and excludes still
+    /// apply
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+    /// The gitignore-style file of paths to skip, `.licensepreambleignore` by
+    /// default. Its patterns are relative to the current directory
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    ignore_file: Option<PathBuf>,
+    /// Also walk the files and directories whose name starts with a dot,
+    /// such as `.vscode`
+    #[arg(long)]
+    hidden: bool,
+    /// Only walk this many directory levels below the source roots, 1 being
+    /// the files directly in them
+    #[arg(long, value_name = "N", value_parser = parse_max_depth)]
+    max_depth: Option<usize>,
+    /// Follow symbolic links to files and directories. Files outside the
+    /// source roots can be modified through them
+    #[arg(long)]
+    follow_links: bool,
+    /// Also walk the vendored directories, such as `third_party`, which
+    /// usually hold code under someone else's copyright
+    #[arg(long)]
+    include_vendored: bool,
+}
+
+impl WalkArgs {
+    /// The walk options these arguments select, exiting with an error if the
+    /// ignore file given doesn't exist.
+    fn options(self) -> WalkOptions {
+        WalkOptions {
+            respect_ignore: !self.no_ignore,
+            default_excludes: !self.no_default_excludes,
+            excludes: self.exclude,
+            includes: self.include,
+            ignore_file: WalkOptions::ignore_file(self.ignore_file),
+            hidden: self.hidden,
+            max_depth: self.max_depth,
+            follow_links: self.follow_links,
+            include_vendored: self.include_vendored,
+            ..WalkOptions::default()
+        }
+    }
+}
+
+/// Which files are skipped when walking the source roots.
+struct WalkOptions {
+    /// Skip the files ignored by git.
+    respect_ignore: bool,
+    /// Skip the directories in [`DEFAULT_EXCLUDES`].
+    default_excludes: bool,
+    /// Gitignore-style patterns of files and directories to skip, relative
+    /// to each source root.
+    excludes: Vec<String>,
+    /// Globs of which a file must match one, unless empty.
+    includes: Vec<String>,
+    /// A gitignore-style file of paths to skip, relative to the current
+    /// directory. It's fine for it not to exist.
+    ignore_file: PathBuf,
+    /// Only keep the files tracked by git.
+    tracked_only: bool,
+    /// Only keep the files changed since this git ref.
+    changed_since: Option<String>,
+    /// Only keep the files staged in git.
+    staged: bool,
+    /// Also walk the files and directories whose name starts with a dot.
+    hidden: bool,
+    /// How deep to walk the source roots, 1 being the files directly in
+    /// them.
+    max_depth: Option<usize>,
+    /// Follow symbolic links instead of skipping them.
+    follow_links: bool,
+    /// Also walk the configured `vendored-dirs`.
+    include_vendored: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        WalkOptions {
+            respect_ignore: true,
+            default_excludes: true,
+            excludes: vec![],
+            includes: vec![],
+            ignore_file: PathBuf::from(IGNORE_FILE),
+            tracked_only: false,
+            changed_since: None,
+            staged: false,
+            hidden: false,
+            max_depth: None,
+            follow_links: false,
+            include_vendored: false,
+        }
+    }
+}
+
+impl WalkOptions {
+    /// The ignore file given with `--ignore-file`, exiting with an error if
+    /// it doesn't exist, or the default one.
+    fn ignore_file(ignore_file: Option<PathBuf>) -> PathBuf {
+        match ignore_file {
+            Some(ignore_file) if !ignore_file.is_file() => {
+                eprintln!("{} doesn't exist", ignore_file.display());
+                std::process::exit(1);
+            }
+            Some(ignore_file) => ignore_file,
+            None => PathBuf::from(IGNORE_FILE),
+        }
+    }
+}
+
+/// Matches paths against `--include` globs, where `*` doesn't cross
+/// directories and `**` does. Exits with an error if a glob is invalid.
+fn include_matcher(includes: &[String]) -> globset::GlobSet {
+    let mut builder = globset::GlobSetBuilder::new();
+    for include in includes {
+        let glob = globset::GlobBuilder::new(include).literal_separator(true).build().unwrap_or_else(|err| {
+            eprintln!("Invalid include pattern `{include}`: {err}");
+            std::process::exit(1);
+        });
+        builder.add(glob);
+    }
+    builder.build().unwrap()
+}
+
+/// `path` with forward slashes and without a leading `./`, so that globs
+/// match the same way on every platform.
+fn normalize_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    path.strip_prefix("./").map(str::to_string).unwrap_or(path)
+}
+
+/// The files selected for processing.
+struct SourceFiles<'a> {
+    /// The supported files along with their comment syntax.
+    files: Vec<(PathBuf, &'a str)>,
+    /// The files with an unsupported extension.
+    unsupported: Vec<PathBuf>,
+    /// The files skipped because git ignores them, along with the reason.
+    ignored: Vec<(PathBuf, String)>,
+    /// The files and directories skipped because of an exclude pattern.
+    excluded: Vec<PathBuf>,
+    /// The exclude patterns that didn't match anything.
+    unused_excludes: Vec<String>,
+    /// How many directories weren't walked because of the depth limit.
+    depth_pruned: usize,
+    /// The symbolic links that weren't followed.
+    symlinks: Vec<PathBuf>,
+    /// The vendored directories that weren't walked.
+    vendored: Vec<PathBuf>,
+    /// How many of the listed paths don't exist.
+    missing: usize,
+}
+
+impl SourceFiles<'_> {
+    /// Only keeps the files whose canonical path is in `paths`.
+    fn retain_paths(&mut self, paths: &HashSet<PathBuf>) {
+        let is_kept = |path: &PathBuf| std::fs::canonicalize(path).is_ok_and(|path| paths.contains(&path));
+        self.files.retain(|(path, _)| is_kept(path));
+        self.unsupported.retain(is_kept);
+    }
+}
+
+/// Expands the glob patterns among the source roots, such as
+/// `packages/*/src`, into the paths they match. Paths without glob
+/// characters are kept as they are. Exits with an error for invalid
+/// patterns and warns about the ones matching nothing.
+fn expand_source_roots(source_roots: Vec<String>) -> Vec<String> {
+    let mut expanded = vec![];
+    for source_root in source_roots {
+        if !source_root.contains(['*', '?', '[']) {
+            expanded.push(source_root);
+            continue;
+        }
+        let paths = glob::glob(&source_root).unwrap_or_else(|err| {
+            eprintln!("Invalid source root pattern `{source_root}`: {err}");
+            std::process::exit(1);
+        });
+        let mut matches: Vec<String> = paths.filter_map(Result::ok)
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        if matches.is_empty() {
+            eprintln!("Warning: `{source_root}` didn't match anything");
+        }
+        matches.sort();
+        expanded.extend(matches);
+    }
+    let mut seen = HashSet::new();
+    expanded.retain(|source_root| seen.insert(source_root.clone()));
+    expanded
+}
+
+/// Like [`source_files`], also returning the files with an unsupported
+/// extension. Source roots can also be files, which are taken as they are.
+/// Files reachable from several roots are only returned once. The excluded
+/// directories aren't walked at all. Files are sorted by path, so that the
+/// output is the same from one run to the next.
+fn scan_source_roots<'a>(config: &'a Config, source_root: Option<Vec<String>>, options: &WalkOptions) -> SourceFiles<'a> {
+    let source_roots = expand_source_roots(source_root.unwrap_or_else(|| config.default_source_roots()));
+    let mut excluded_dirs = config.exclude_dirs.clone();
+    if options.default_excludes {
+        excluded_dirs.extend(DEFAULT_EXCLUDES.iter().map(|name| name.to_string()));
+    }
+    let excluded_dirs = Arc::new(excluded_dirs);
+    let vendored_dirs = Arc::new(if options.include_vendored { vec![] } else { config.vendored_dirs.clone() });
+    let vendored = Arc::new(Mutex::new(vec![]));
+    let ignore_file = read_ignore_file(&options.ignore_file);
+
+    let mut seen = HashSet::new();
+    let mut ignore_rules = IgnoreRules::default();
+    let mut files = vec![];
+    let mut unsupported = vec![];
+    let mut ignored = vec![];
+    let mut excluded = vec![];
+    let mut used_excludes = HashSet::new();
+    let mut depth_pruned = 0;
+    let mut symlinks = vec![];
+    // The canonical directories walked so far, so that symbolic links back
+    // to one of them aren't followed in a loop.
+    let visited_dirs = Arc::new(Mutex::new(HashSet::new()));
+    for source_root in source_roots {
+        let Ok(metadata) = std::fs::metadata(&source_root) else {
+            continue;
+        };
+        if metadata.is_file() && !options.follow_links && Path::new(&source_root).is_symlink() {
+            symlinks.push(PathBuf::from(&source_root));
+            continue;
+        }
+
+        // Patterns are relative to the source root, or to the current
+        // directory for files given directly.
+        let is_file = metadata.is_file();
+        let excludes_root = if is_file { Path::new(".") } else { Path::new(&source_root) };
+        let excludes = Arc::new(Excludes::new(excludes_root, &options.excludes, ignore_file.clone()));
+        let paths: Vec<PathBuf> = if is_file {
+            let path = PathBuf::from(&source_root);
+            if excludes.is_excluded(&path, false) { vec![] } else { vec![path] }
+        } else {
+            let (hidden, follow_links) = (options.hidden, options.follow_links);
+            let walk = jwalk::WalkDir::new(&source_root)
+                .skip_hidden(false)
+                .sort(true)
+                .follow_links(follow_links)
+                .max_depth(options.max_depth.unwrap_or(usize::MAX))
+                .process_read_dir({
+                    let (excluded_dirs, excludes) = (excluded_dirs.clone(), excludes.clone());
+                    let (vendored_dirs, vendored) = (vendored_dirs.clone(), vendored.clone());
+                    let visited_dirs = visited_dirs.clone();
+                    move |_, dir, _, children| {
+                        if follow_links {
+                            if let Ok(dir) = std::fs::canonicalize(dir) {
+                                visited_dirs.lock().unwrap().insert(dir);
+                            }
+                        }
+                        children.retain(|child| {
+                            child.as_ref().map_or(true, |child| {
+                                if !hidden && child.file_name.to_string_lossy().starts_with('.') {
+                                    return false;
+                                }
+                                if follow_links && child.file_type.is_dir() && child.path_is_symlink() {
+                                    let target = std::fs::canonicalize(child.path());
+                                    if target.map_or(true, |target| visited_dirs.lock().unwrap().contains(&target)) {
+                                        return false;
+                                    }
+                                }
+                                let is_dir = child.file_type.is_dir();
+                                if is_dir && vendored_dirs.iter().any(|name| child.file_name == name.as_str()) {
+                                    vendored.lock().unwrap().push(dir.join(&child.file_name));
+                                    return false;
+                                }
+                                let is_excluded_dir = is_dir && excluded_dirs.iter().any(|name| child.file_name == name.as_str());
+                                !is_excluded_dir && !excludes.is_excluded(&dir.join(&child.file_name), is_dir)
+                            })
+                        });
+                    }
+                });
+            let mut paths = vec![];
+            for entry in walk {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        eprintln!("Warning: skipping {err}");
+                        continue;
+                    }
+                };
+                if entry.file_type.is_symlink() {
+                    symlinks.push(entry.path());
+                } else if entry.file_type.is_file() {
+                    paths.push(entry.path());
+                } else if entry.file_type.is_dir() && Some(entry.depth) == options.max_depth {
+                    depth_pruned += 1;
+                }
+            }
+            paths
+        };
+        for path in paths {
+            if !seen.insert(std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone())) {
+                continue;
+            }
+            if let Some(reason) = options.respect_ignore.then(|| ignore_rules.ignored_by(&path)).flatten() {
+                ignored.push((path, reason));
+                continue;
+            }
+
+            match config.comment_syntax(&path) {
+                Some(comment_syntax) => files.push((path, comment_syntax)),
+                None if is_file => {
+                    eprintln!("Warning: {path:?} has an unsupported extension");
+                    unsupported.push(path);
+                }
+                None => unsupported.push(path),
+            }
+        }
+        excluded.extend(excludes.take_excluded());
+        used_excludes.extend(options.excludes.iter().filter(|pattern| excludes.was_used(pattern)));
+    }
+    let unused_excludes = options.excludes.iter()
+        .filter(|pattern| !used_excludes.contains(pattern))
+        .cloned()
+        .collect();
+    // The walk is sorted within each root, but roots can overlap or come
+    // in any order.
+    files.sort();
+    unsupported.sort();
+    ignored.sort();
+    excluded.sort();
+    symlinks.sort();
+    let mut vendored = std::mem::take(&mut *vendored.lock().unwrap());
+    vendored.sort();
+    SourceFiles { files, unsupported, ignored, excluded, unused_excludes, depth_pruned, symlinks, vendored, missing: 0 }
+}
+
+/// Reads the files to process from stdin, one path per line or, with
+/// `null`, separated by NUL bytes. Paths that don't exist or can't be
+/// represented on this platform are warned about.
+fn read_source_files(config: &Config, null: bool) -> SourceFiles<'_> {
+    let mut input = vec![];
+    if let Err(err) = std::io::stdin().read_to_end(&mut input) {
+        eprintln!("Couldn't read the file paths from stdin: {err}");
+        std::process::exit(1);
+    }
+    let separator = if null { b'\0' } else { b'\n' };
+
+    let paths = input.split(|byte| *byte == separator)
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+        .filter_map(path_from_bytes);
+    listed_source_files(config, paths)
+}
+
+/// A path read as bytes, which can be anything but NUL on Unix.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    Some(PathBuf::from(<std::ffi::OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(bytes)))
+}
+
+/// A path read as bytes, which must be valid UTF-8 on other platforms.
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    match std::str::from_utf8(bytes) {
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) => {
+            eprintln!("Warning: skipping {:?}, file paths must be valid UTF-8", String::from_utf8_lossy(bytes));
+            None
+        }
+    }
+}
+
+/// How far into a file NUL bytes are looked for to tell it's binary.
+const BINARY_CHECK_LEN: usize = 8192;
+
+/// Why a source file couldn't be read as text.
+enum ReadError {
+    Io(std::io::Error),
+    /// It has NUL bytes near the start.
+    Binary,
+    /// It isn't valid UTF-8, and `lossy` wasn't set.
+    NotUtf8,
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(err) => err.fmt(f),
+            ReadError::Binary => f.write_str("it looks binary"),
+            ReadError::NotUtf8 => f.write_str("it isn't valid UTF-8"),
+        }
+    }
+}
+
+/// Reads a source file, refusing binary files. Files that aren't valid
+/// UTF-8 are read as Latin-1 with `lossy`, and refused otherwise.
+fn read_source_file(path: &Path, lossy: bool) -> Result<String, ReadError> {
+    let bytes = std::fs::read(path).map_err(ReadError::Io)?;
+    if bytes[..bytes.len().min(BINARY_CHECK_LEN)].contains(&0) {
+        return Err(ReadError::Binary);
+    }
+    match String::from_utf8(bytes) {
+        Ok(file_contents) => Ok(file_contents),
+        Err(err) if lossy => Ok(err.into_bytes().into_iter().map(char::from).collect()),
+        Err(_) => Err(ReadError::NotUtf8),
+    }
+}
+
+/// Reads a source file like [`read_source_file`] without `lossy`, warning
+/// about the ones that can't be read as text so they can be skipped.
+fn read_source_file_or_warn(path: &Path) -> Option<String> {
+    match read_source_file(path, false) {
+        Ok(file_contents) => Some(file_contents),
+        Err(err) => {
+            eprintln!("Warning: skipping {path:?}, {err}");
+            None
+        }
+    }
+}
+
+/// Writes a source file's new contents, reporting the error and returning
+/// `false` if it can't be written so the file can be counted as failed.
+fn write_source_file(path: &Path, contents: &str) -> bool {
+    match std::fs::write(path, contents) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("Failed to write {path:?}: {err}");
+            false
+        }
+    }
+}
+
+/// How many bytes past the length of the preamble are read when looking
+/// for it at the top of a file, leaving room for a shebang or a BOM.
+const PREAMBLE_SEARCH_SLACK: usize = 4096;
+
+/// Whether the preamble is in the comments at the top of a file, only
+/// reading as much of it as needed. Files where it isn't found may still
+/// have it after a long comment.
+fn has_preamble_near_top(path: &Path, prefixed_preamble: &str, comment_syntax: &str) -> bool {
+    let limit = (prefixed_preamble.len() + PREAMBLE_SEARCH_SLACK) as u64;
+    let mut bytes = vec![];
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    if file.take(limit).read_to_end(&mut bytes).is_err() {
+        return false;
+    }
+    // The limit may cut a character in half.
+    let text = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        Err(err) => std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap(),
+    };
+    contains_preamble(text, prefixed_preamble, comment_syntax)
+}
+
+/// Whether a file is larger than `max_file_size` bytes, 0 meaning no limit.
+fn is_too_large(path: &Path, max_file_size: u64) -> bool {
+    max_file_size > 0 && std::fs::metadata(path).is_ok_and(|metadata| metadata.len() > max_file_size)
+}
+
+/// The flags of `add` that change what happens to a file.
+#[derive(Default)]
+struct AddRules {
+    /// The header is an SPDX one, so any SPDX header counts as present.
+    spdx: bool,
+    strict_year: bool,
+    force_minified: bool,
+    replace_existing: bool,
+}
+
+/// What `add` does with a source file, see [`add_decision`].
+enum AddDecision {
+    /// The header is added, giving these contents.
+    Add(String),
+    /// A foreign license header is replaced with ours, giving these
+    /// contents.
+    Replace(String),
+    Present,
+    Outdated,
+    /// It has a license header from elsewhere, which is kept.
+    Foreign,
+    OptedOut,
+    Generated,
+    /// A PHP file starting with inline HTML.
+    InlineHtml,
+    Minified,
+}
+
+/// Decides what `add` does with a file that has been read, so that `diff`
+/// and `watch` make the same changes.
+fn add_decision(
+    config: &Config,
+    path: &Path,
+    file_contents: &str,
+    header: &str,
+    comment_syntax: &str,
+    rules: &AddRules,
+) -> AddDecision {
+    if has_ignore_directive(file_contents) {
+        return AddDecision::OptedOut;
+    }
+    if is_generated(config, file_contents) {
+        return AddDecision::Generated;
+    }
+    if starts_with_inline_html(path, file_contents, comment_syntax) {
+        return AddDecision::InlineHtml;
+    }
+    if !rules.force_minified && looks_minified(path, file_contents) {
+        return AddDecision::Minified;
+    }
+    let presence = if rules.spdx {
+        if has_spdx_header(file_contents) { Presence::Present } else { Presence::Missing }
+    } else if let Some(markers) = &config.markers {
+        markers.presence(file_contents, header, comment_syntax)
+    } else {
+        presence(file_contents, header, comment_syntax, rules.strict_year)
+    };
+    match presence {
+        Presence::Present => return AddDecision::Present,
+        Presence::Outdated => return AddDecision::Outdated,
+        Presence::Missing => {}
+    }
+
+    // A license header from elsewhere is replaced rather than stacked under
+    // ours.
+    let (prologue, body) = split_prologue(file_contents, comment_syntax);
+    let foreign_header = split_leading_comment(body, comment_syntax)
+        .filter(|(leading_comment, _)| !rules.spdx && looks_like_notice(leading_comment, comment_syntax));
+    match foreign_header {
+        Some((_, rest)) if rules.replace_existing => AddDecision::Replace(insert_header(
+            &join_prologue(prologue, rest),
+            header,
+            config.blank_lines,
+            comment_syntax,
+        )),
+        Some(_) => AddDecision::Foreign,
+        None => AddDecision::Add(insert_header(file_contents, header, config.blank_lines, comment_syntax)),
+    }
+}
+
+/// Selects the files in `paths`, warning about the ones that don't exist.
+fn listed_source_files(config: &Config, paths: impl IntoIterator<Item = PathBuf>) -> SourceFiles<'_> {
+    let mut source_files = SourceFiles {
+        files: vec![],
+        unsupported: vec![],
+        ignored: vec![],
+        excluded: vec![],
+        unused_excludes: vec![],
+        depth_pruned: 0,
+        symlinks: vec![],
+        vendored: vec![],
+        missing: 0,
+    };
+    for path in paths {
+        if !path.is_file() {
+            eprintln!("Warning: {path:?} doesn't exist");
+            source_files.missing += 1;
+            continue;
+        }
+        match config.comment_syntax(&path) {
+            Some(comment_syntax) => source_files.files.push((path, comment_syntax)),
+            None => source_files.unsupported.push(path),
+        }
+    }
+    source_files
+}
+
+/// Selects the files from stdin when asked to, or when the only source root
+/// is `-`, and by walking the source roots otherwise. Only the files
+/// staged, tracked or changed by git as requested and matching an include
+/// are kept, exiting with an error if there's none. Staged files go through
+/// the same filters as the walk, so that the pre-commit hook agrees with
+/// `check`.
+fn select_source_files<'a>(
+    config: &'a Config,
+    source_root: Option<Vec<String>>,
+    stdin: bool,
+    null: bool,
+    options: &WalkOptions,
+) -> SourceFiles<'a> {
+    let mut source_files = if stdin || source_root.as_deref().is_some_and(|roots| roots == ["-"]) {
+        read_source_files(config, null)
+    } else {
+        scan_source_roots(config, source_root, options)
+    };
+    if options.staged {
+        let staged = staged_files().unwrap_or_else(|err| {
+            eprintln!("--staged needs a git repository: {err}");
+            std::process::exit(1);
+        });
+        source_files.retain_paths(&staged);
+    }
+    if options.tracked_only {
+        let tracked = tracked_files().unwrap_or_else(|err| {
+            eprintln!("--tracked-only needs a git repository: {err}");
+            std::process::exit(1);
+        });
+        source_files.retain_paths(&tracked);
+    }
+    if let Some(since) = &options.changed_since {
+        let changed = changed_files(since).unwrap_or_else(|err| {
+            eprintln!("Couldn't list the files changed since {since}: {err}");
+            std::process::exit(1);
+        });
+        source_files.retain_paths(&changed);
+    }
+    if options.includes.is_empty() {
+        return source_files;
+    }
+
+    let includes = include_matcher(&options.includes);
+    source_files.files.retain(|(path, _)| includes.is_match(normalize_path(path)));
+    source_files.unsupported.retain(|path| includes.is_match(normalize_path(path)));
+    if source_files.files.is_empty() && source_files.unsupported.is_empty() {
+        eprintln!("No files match --include {}", options.includes.join(", "));
+        std::process::exit(1);
+    }
+    source_files
+}
+
+/// The text up to the end of the first sentence, on a single line.
+fn first_sentence(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    match text.find(". ") {
+        Some(end) => text[..=end].to_string(),
+        None => text,
+    }
+}
+
+/// Parses license names given separately or joined with ` OR `, failing
+/// with the first unknown name.
+fn parse_license_expression<'a, 'b>(
+    licenses: &'a [Document<LicenseInfo>],
+    names: &'b [String],
+) -> Result<Vec<&'a Document<LicenseInfo>>, &'b str> {
+    names.iter()
+        .flat_map(|name| name.split(" OR "))
+        .map(|name| name.trim().trim_start_matches('(').trim_end_matches(')'))
+        .filter(|name| !name.is_empty())
+        .map(|name| lookup_license(licenses, name).ok_or(name))
+        .collect()
+}
+
+/// Writes a `LICENSE-<NAME>` file for each license and a PREAMBLE offering
+/// the choice between them.
+fn init_dual(
+    license_documents: &[&Document<LicenseInfo>],
+    placeholders: &Placeholders,
+    dir: &Path,
+    preamble_path: &Path,
+    force: bool,
+) -> bool {
+    let short_names: Vec<String> = license_documents.iter()
+        .map(|document| document.metadata.spdx_id.split('-').next().unwrap().to_uppercase())
+        .collect();
+    let has_duplicates = short_names.iter().enumerate().any(|(i, name)| short_names[..i].contains(name));
+
+    let mut succeeded = true;
+    let mut options = vec![];
+    for (document, short_name) in license_documents.iter().zip(short_names) {
+        let info = &document.metadata;
+        let file_name = if has_duplicates {
+            format!("LICENSE-{}", info.spdx_id.to_uppercase())
+        } else {
+            format!("LICENSE-{short_name}")
+        };
+        let license_path = &dir.join(&file_name);
+        if prepare_overwrite(license_path, force) {
+            std::fs::write(license_path, placeholders.fill(document.content.trim())).unwrap();
+        } else {
+            succeeded = false;
+        }
+        options.push(format!(" * {} ({file_name})", info.title));
+    }
+
+    if prepare_overwrite(preamble_path, force) {
+        let expression: Vec<&str> = license_documents.iter().map(|document| document.metadata.spdx_id.as_str()).collect();
+        let preamble_contents = format!(
+            "SPDX-License-Identifier: {}\n\nLicensed under either of\n\n{}\n\nat your option.",
+            expression.join(" OR "),
+            options.join("\n"),
+        );
+        std::fs::write(preamble_path, preamble_contents).unwrap();
+    } else {
+        succeeded = false;
+    }
+    succeeded
+}
+
+/// The directory `init` writes to, empty for the current one and created
+/// when `create_dir` is set. Exits with an error if it's missing or isn't a
+/// directory.
+fn init_dir(dir: Option<PathBuf>, create_dir: bool) -> PathBuf {
+    let Some(dir) = dir else {
+        return PathBuf::new();
+    };
+    match std::fs::metadata(&dir) {
+        Ok(metadata) if metadata.is_dir() => {}
+        Ok(_) => {
+            eprintln!("{} isn't a directory", dir.display());
+            std::process::exit(1);
+        }
+        Err(_) if create_dir => std::fs::create_dir_all(&dir).unwrap(),
+        Err(_) => {
+            eprintln!("{} doesn't exist, pass --create-dir to create it", dir.display());
+            std::process::exit(1);
+        }
+    }
+    dir
+}
+
+/// Parses an `init --scope` argument.
+fn parse_scope(argument: &str) -> Result<(PathBuf, String), String> {
+    let (dir, license) = argument.split_once('=').ok_or("expected <DIR>=<LICENSE>")?;
+    if dir.is_empty() || license.trim().is_empty() {
+        return Err(String::from("expected <DIR>=<LICENSE>"));
+    }
+    Ok((PathBuf::from(dir), license.to_string()))
+}
+
+/// Parses a `--max-depth` argument.
+fn parse_max_depth(argument: &str) -> Result<usize, String> {
+    match argument.parse() {
+        Ok(0) => Err(String::from("the depth must be at least 1")),
+        Ok(depth) => Ok(depth),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Warns when PREAMBLE is longer than `max_lines`, returning whether it
+/// was.
+fn warn_long_preamble(preamble_contents: &str, max_lines: usize) -> bool {
+    let lines = preamble_contents.lines().count();
+    if lines <= max_lines {
+        return false;
+    }
+    eprintln!();
+    eprintln!("WARNING: PREAMBLE is {lines} lines long and `add` copies it into every source file.");
+    eprintln!("WARNING: Consider replacing it with a short header, e.g. with `init --preamble header --force`.");
+    eprintln!();
+    true
+}
+
+/// Whether `path` can be written, removing the existing file first when
+/// `force` is set.
+fn prepare_overwrite(path: &Path, force: bool) -> bool {
+    if path.symlink_metadata().is_err() {
+        return true;
+    }
+    if force {
+        std::fs::remove_file(path).unwrap();
+        return true;
+    }
+    eprintln!("Refusing to overwrite {} file, pass --force to replace it", path.display());
+    false
+}
+
+#[cfg(unix)]
+fn symlink_file(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink_file(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(original, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink_file(_original: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// The path of `target` relative to the directory containing `link`, which
+/// is how symlink targets are resolved.
+fn symlink_target(target: &Path, link: &Path) -> PathBuf {
+    let target = std::path::absolute(target).unwrap();
+    let link_dir = std::path::absolute(link).unwrap().parent().unwrap().to_path_buf();
+    let target_components: Vec<_> = target.components().collect();
+    let link_dir_components: Vec<_> = link_dir.components().collect();
+    let shared = target_components.iter().zip(&link_dir_components).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in shared..link_dir_components.len() {
+        relative.push("..");
+    }
+    relative.extend(&target_components[shared..]);
+    relative
+}
+
+/// Links PREAMBLE to LICENSE, copying LICENSE instead when symlinks can't be
+/// created, e.g. on Windows without Developer Mode.
+fn link_preamble(license_path: &Path, preamble_path: &Path) {
+    link_or_copy(license_path, preamble_path, symlink_file);
+}
+
+/// Links PREAMBLE to LICENSE with `symlink`, copying LICENSE when it fails.
+fn link_or_copy(license_path: &Path, preamble_path: &Path, symlink: fn(&Path, &Path) -> std::io::Result<()>) {
+    if let Err(err) = symlink(&symlink_target(license_path, preamble_path), preamble_path) {
+        eprintln!("Couldn't create the PREAMBLE symlink ({err}), copying LICENSE into it instead");
+        std::fs::copy(license_path, preamble_path).unwrap();
+    }
+}
+
+/// Writes the LICENSE file and PREAMBLE as laid out, returning `false` if
+/// either already existed and `force` isn't set. `header` is the license's
+/// standard header, if it has one.
+fn init(
+    license_contents: &str,
+    header: Option<&str>,
+    license_path: &Path,
+    preamble_path: &Path,
+    layout: PreambleLayout,
+    force: bool,
+) -> bool {
+    let mut succeeded = true;
+    if prepare_overwrite(license_path, force) {
+        std::fs::write(license_path, license_contents).unwrap();
+    } else {
+        succeeded = false;
+    }
+    if prepare_overwrite(preamble_path, force) {
+        match (layout, header) {
+            (PreambleLayout::Header, Some(header)) => std::fs::write(preamble_path, header).unwrap(),
+            (PreambleLayout::Header | PreambleLayout::Full, _) => {
+                std::fs::write(preamble_path, license_contents).unwrap()
+            }
+            (PreambleLayout::Symlink, _) => link_preamble(license_path, preamble_path),
+        }
+    } else {
+        succeeded = false;
+    }
+    succeeded
+}
+
+/// The `LICENSE-<NAME>` files that `init` writes next to LICENSE for a dual
+/// license, sorted by name.
+fn dual_license_files(license_path: &Path) -> Vec<PathBuf> {
+    let dir = license_path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let prefix = format!("{}-", license_path.file_name().unwrap_or_default().to_string_lossy());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .map(|entry| license_path.with_file_name(entry.file_name()))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// The license files of the project: LICENSE, or the files of a dual
+/// license when there's no LICENSE.
+fn license_files(license_path: &Path) -> Vec<PathBuf> {
+    match dual_license_files(license_path) {
+        dual_license_files if !license_path.exists() && !dual_license_files.is_empty() => dual_license_files,
+        _ => vec![license_path.to_path_buf()],
+    }
+}
+
+/// The SPDX id of the project's license, from the configuration file or by
+/// identifying LICENSE. A dual license is identified from its
+/// `LICENSE-<NAME>` files, giving an expression such as `MIT OR
+/// Apache-2.0`.
+fn project_spdx_id(licenses: &[Document<LicenseInfo>], config: &Config, license_path: &Path) -> Option<String> {
+    if config.custom_license {
+        return None;
+    }
+    config.license.clone().or_else(|| {
+        let spdx_ids = license_files(license_path).iter().map(|path| {
+            let license_contents = std::fs::read_to_string(path).ok()?;
+            identify(&license_contents, licenses)
+                .filter(|(_, confidence)| *confidence >= CONFIDENCE_THRESHOLD)
+                .map(|(license_document, _)| license_document.metadata.spdx_id.as_str())
+        }).collect::<Option<Vec<&str>>>()?;
+        Some(spdx_ids.join(" OR "))
+    })
+}
+
+/// The nearest directory from `dir` up with a PREAMBLE, LICENSE or
+/// configuration file.
+fn find_project_root(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|dir| {
+        [CONFIG_PATH, "PREAMBLE", "LICENSE"].iter().any(|marker| dir.join(marker).symlink_metadata().is_ok())
+    })
+}
+
+/// Reads PREAMBLE, exiting with an error if it's missing, a dangling
+/// symlink or unreadable.
+fn read_preamble(preamble_path: &Path) -> String {
+    if !preamble_path.exists() {
+        if let Ok(target) = std::fs::read_link(preamble_path) {
+            eprintln!(
+                "{} is a symlink to {target:?}, which doesn't exist. Run `init --force` to recreate it",
+                preamble_path.display()
+            );
+        } else {
+            eprintln!("{} doesn't exist. Run init first, or set `preamble` in {CONFIG_PATH}", preamble_path.display());
+        }
+        std::process::exit(1);
+    }
+    std::fs::read_to_string(preamble_path).unwrap_or_else(|err| {
+        eprintln!("Couldn't read {}: {err}", preamble_path.display());
+        std::process::exit(1);
+    })
+}
+
+/// Reads PREAMBLE with its variables filled in, see [`render_preamble`].
+fn load_preamble(config: &Config, preamble_path: &Path) -> String {
+    render_preamble(config, &read_preamble(preamble_path), &[])
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    let licenses: Vec<Document<LicenseInfo>> = LICENSE_FILES.iter().map(|license| yaml_front_matter::YamlFrontMatter::parse(license).unwrap()).collect();
+    // `init` creates the project in the current directory rather than
+    // looking for one above it.
+    let project_root = match (cli.project_root, &cli.command) {
+        (Some(project_root), _) => Some(project_root),
+        (None, Commands::Init { .. }) => None,
+        (None, _) => std::env::current_dir().ok()
+            .and_then(|dir| find_project_root(&dir).map(Path::to_path_buf)),
+    };
+    if let Some(project_root) = project_root {
+        if let Err(err) = std::env::set_current_dir(&project_root) {
+            eprintln!("Can't use {} as the project root: {err}", project_root.display());
+            std::process::exit(1);
+        }
+        if matches!(cli.command, Commands::Add { verbose: true, .. } | Commands::Normalize { verbose: true, .. }) {
+            eprintln!("Project root: {}", project_root.display());
+        }
+    }
+
+    let licenses = load_licenses();
+    let config_path = Path::new(CONFIG_PATH);
+    let mut config = Config::load(config_path);
+    config.apply_styles(cli.style);
     let license_path = Path::new("LICENSE");
-    let preamble_path = Path::new("PREAMBLE");
+    let preamble_path = config.preamble.as_deref().unwrap_or(Path::new("PREAMBLE"));
+    let notice_path = Path::new("NOTICE");
 
     match cli.command {
-        Commands::Init { license } => {
-            let license_document = licenses.iter().find(|document| {
-                let info = &document.metadata;
-                info.title == license || info.spdx_id == license
-            }).expect("Invalid license, list available licenses with `list`");
-
-            if !license_path.exists() {
-                std::fs::write(license_path, &license_document.content.trim()).unwrap();
+        Commands::Init {
+            scopes,
+            create_dirs,
+            fullname,
+            year,
+            email,
+            project,
+            no_git,
+            force,
+            preamble,
+            ..
+        } if !scopes.is_empty() => {
+            // Everything is checked before writing anything, so that a typo
+            // doesn't leave the project half initialized.
+            let mut scope_licenses = vec![];
+            for (dir, names) in &scopes {
+                let license_documents = parse_license_expression(&licenses, std::slice::from_ref(names))
+                    .unwrap_or_else(|name| unknown_license(&licenses, name));
+                scope_licenses.push(license_documents);
+                match std::fs::metadata(dir) {
+                    Ok(metadata) if metadata.is_dir() => {}
+                    Ok(_) => {
+                        eprintln!("{} isn't a directory", dir.display());
+                        std::process::exit(1);
+                    }
+                    Err(_) if create_dirs => {}
+                    Err(_) => {
+                        eprintln!("{} doesn't exist, pass --create-dirs to create it", dir.display());
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            let placeholders = Placeholders::with_defaults(fullname, year, email, project, !no_git);
+            placeholders.print();
+            let mut rows = vec![];
+            let mut succeeded = true;
+            for ((dir, _), license_documents) in scopes.iter().zip(scope_licenses) {
+                std::fs::create_dir_all(dir).unwrap();
+                let (scope_license_path, scope_preamble_path) = (dir.join(license_path), dir.join(preamble_path));
+                let (files, initialized) = if let [license_document] = license_documents[..] {
+                    let license_contents = placeholders.fill(license_document.content.trim());
+                    let header = standard_header(&license_document.metadata.spdx_id)
+                        .map(|header| placeholders.fill(&header));
+                    let initialized = init(
+                        &license_contents,
+                        header.as_deref(),
+                        &scope_license_path,
+                        &scope_preamble_path,
+                        preamble,
+                        force,
+                    );
+                    (format!("{}, {}", scope_license_path.display(), scope_preamble_path.display()), initialized)
+                } else {
+                    let initialized = init_dual(&license_documents, &placeholders, dir, &scope_preamble_path, force);
+                    (format!("LICENSE-*, {}", scope_preamble_path.display()), initialized)
+                };
+                let expression: Vec<&str> = license_documents.iter()
+                    .map(|document| document.metadata.spdx_id.as_str())
+                    .collect();
+                let status = if initialized { "created" } else { "skipped" };
+                rows.push((dir.display().to_string(), expression.join(" OR "), status, files));
+                succeeded &= initialized;
+            }
+
+            println!();
+            println!("{:<30} {:<30} {:<10} FILES", "SCOPE", "LICENSE", "STATUS");
+            for (dir, expression, status, files) in rows {
+                println!("{dir:<30} {expression:<30} {status:<10} {files}");
+            }
+            if !succeeded {
+                std::process::exit(1);
+            }
+        }
+        Commands::Init { license_file: Some(license_file), dir, create_dir, force, preamble, .. } => {
+            let dir = init_dir(dir, create_dir);
+            let license_contents = match std::fs::read_to_string(&license_file) {
+                Ok(contents) if contents.trim().is_empty() => {
+                    eprintln!("{} is empty", license_file.display());
+                    std::process::exit(1);
+                }
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+                    eprintln!("{} isn't valid UTF-8", license_file.display());
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Couldn't read {}: {err}", license_file.display());
+                    std::process::exit(1);
+                }
+            };
+            let preamble_path = dir.join(preamble_path);
+            if !init(license_contents.trim(), None, &dir.join(license_path), &preamble_path, preamble, force) {
+                std::process::exit(1);
+            }
+            warn_long_preamble(&read_preamble(&preamble_path), config.max_preamble_lines);
+            let config_path = dir.join(config_path);
+            let config = Config { license: None, custom_license: true, ..Config::load(&config_path) };
+            config.save(&config_path);
+            println!("Recorded the custom license in {}", config_path.display());
+        }
+        Commands::Init {
+            licenses: names,
+            fullname,
+            year,
+            email,
+            project,
+            no_git,
+            license_file: None,
+            dir,
+            create_dir,
+            no_adopt,
+            force,
+            preamble,
+            notice: with_notice,
+            holder,
+            ..
+        } => {
+            let dir = init_dir(dir, create_dir);
+            let (license_path, preamble_path) = (&dir.join(license_path), &dir.join(preamble_path));
+            let preamble_state = PreambleState::of(preamble_path);
+            let repaired = preamble_state == PreambleState::Dangling && !force;
+            if repaired {
+                let target = std::fs::read_link(preamble_path).unwrap();
+                std::fs::remove_file(preamble_path).unwrap();
+                println!("Removed {}, a symlink to the missing {}", preamble_path.display(), target.display());
+            }
+            let existing = if license_path.symlink_metadata().is_ok() {
+                (!force).then(|| license_path.clone())
             } else {
-                eprintln!("Refusing to overwrite LICENSE file")
+                find_license_file(&dir).filter(|_| !no_adopt)
+            };
+            if let Some(existing) = existing {
+                if preamble_state == PreambleState::Present && !force {
+                    println!(
+                        "Already initialized, {} and {} exist. Pass --force to replace them",
+                        existing.display(),
+                        preamble_path.display()
+                    );
+                    std::process::exit(EXIT_ALREADY_INITIALIZED);
+                }
+                let adopt = existing == *license_path || !std::io::stdin().is_terminal() || {
+                    let question = format!("{} already has the license, write only PREAMBLE for it?", existing.display());
+                    ask_with_default(&question, Some(String::from("y"))).is_some_and(|answer| answer.starts_with(['y', 'Y']))
+                };
+                if adopt {
+                    let placeholders = Placeholders::with_defaults(fullname, year, email, project, !no_git);
+                    if !adopt_license_file(&licenses, &placeholders, &existing, preamble_path, preamble, force) {
+                        std::process::exit(1);
+                    }
+                    warn_long_preamble(&read_preamble(preamble_path), config.max_preamble_lines);
+                    if let (true, Some(holder), Some(year)) = (with_notice, holder, placeholders.year) {
+                        if !notice(&licenses, &existing, &dir.join(notice_path), &holder, year) {
+                            std::process::exit(1);
+                        }
+                    }
+                    std::process::exit(if repaired { EXIT_REPAIRED } else { EXIT_ADOPTED });
+                }
             }
-            if !preamble_path.exists() {
-                #[allow(deprecated)]
-                std::fs::soft_link(license_path, preamble_path).unwrap();
+            let declared = names.is_empty().then(|| declared_license(&dir)).flatten();
+            let (license_documents, fullname, year) = if !names.is_empty() {
+                let license_documents = parse_license_expression(&licenses, &names)
+                    .unwrap_or_else(|name| unknown_license(&licenses, name));
+                (license_documents, fullname, year)
+            } else if let Some(declared) = declared {
+                println!("Using the license declared in {}: {}", declared.manifest, declared.expression);
+                let expression = [declared.expression];
+                let license_documents = parse_license_expression(&licenses, &expression).unwrap_or_else(|name| {
+                    eprintln!(
+                        "{} declares the license `{name}`, which isn't one of the embedded licenses. Pass a license explicitly",
+                        declared.manifest
+                    );
+                    std::process::exit(1);
+                });
+                (license_documents, fullname, year)
+            } else if std::io::stdin().is_terminal() {
+                let license_document = pick_license(&licenses);
+                let default_fullname = fullname.or_else(|| (!no_git).then(|| git_config("user.name")).flatten());
+                let fullname = ask_with_default("Copyright holder?", default_fullname);
+                let year = loop {
+                    let default_year = year.unwrap_or_else(current_year).to_string();
+                    match ask_with_default("Year?", Some(default_year)).map(|year| year.parse::<u16>()) {
+                        Some(Ok(year)) => break Some(year),
+                        _ => eprintln!("The year must be a number"),
+                    }
+                };
+                (vec![license_document], fullname, year)
+            } else {
+                Cli::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        format!("the following required arguments were not provided:\n  <{LICENSE_VALUE_NAME}>..."),
+                    )
+                    .exit();
+            };
+            let placeholders = Placeholders::with_defaults(fullname, year, email, project, !no_git);
+            placeholders.print();
+            let initialized = if let [license_document] = license_documents[..] {
+                let license_contents = placeholders.fill(license_document.content.trim());
+                if preamble_state == PreambleState::Present && !force {
+                    std::fs::write(license_path, &license_contents).unwrap();
+                    println!("Recreated {} for the existing {}", license_path.display(), preamble_path.display());
+                    std::process::exit(EXIT_RECREATED_LICENSE);
+                }
+                let header = standard_header(&license_document.metadata.spdx_id).map(|header| placeholders.fill(&header));
+                init(&license_contents, header.as_deref(), license_path, preamble_path, preamble, force)
             } else {
-                eprintln!("Refusing to overwrite PREAMBLE file")
+                init_dual(&license_documents, &placeholders, &dir, preamble_path, force)
+            };
+            if !initialized {
+                std::process::exit(1);
+            }
+            warn_long_preamble(&read_preamble(preamble_path), config.max_preamble_lines);
+            if let (true, Some(holder), Some(year)) = (with_notice, holder, placeholders.year) {
+                if !notice(&licenses, license_path, &dir.join(notice_path), &holder, year) {
+                    std::process::exit(1);
+                }
+            }
+            // Recorded for `add --spdx` and `reuse`, which can't always tell
+            // the license from LICENSE.
+            let expression: Vec<&str> = license_documents.iter()
+                .map(|document| document.metadata.spdx_id.as_str())
+                .collect();
+            let config_path = dir.join(config_path);
+            let config = Config { license: Some(expression.join(" OR ")), custom_license: false, ..Config::load(&config_path) };
+            config.save(&config_path);
+            println!("Recorded the license in {}", config_path.display());
+            if repaired {
+                std::process::exit(EXIT_REPAIRED);
             }
         }
-        Commands::List => {
-            for document in licenses {
-                let info = document.metadata;
-                println!("{:<60}   -  short:  {:}", info.title, info.spdx_id);
+        Commands::List {
+            verbose,
+            format,
+            json,
+            sort,
+            featured_only,
+            no_description,
+            ids,
+            table,
+            columns,
+            filters,
+        } => {
+            filters.validate(&licenses);
+            let is_featured = |info: &LicenseInfo| info.featured.unwrap_or(false);
+            let mut infos: Vec<&LicenseInfo> = licenses.iter()
+                .map(|document| &document.metadata)
+                .filter(|info| filters.matches(info) && (!featured_only || is_featured(info)))
+                .collect();
+            match sort {
+                ListSort::Featured => infos.sort_by_key(|info| (!is_featured(info), info.spdx_id.to_lowercase())),
+                ListSort::Title => infos.sort_by_key(|info| info.title.to_lowercase()),
+                ListSort::Id => infos.sort_by_key(|info| info.spdx_id.to_lowercase()),
+            }
+
+            if json || format == ListFormat::Json {
+                let listed: Vec<ListedLicense> = infos.iter()
+                    .map(|info| ListedLicense {
+                        title: &info.title,
+                        spdx_id: &info.spdx_id,
+                        featured: is_featured(info),
+                        copyleft: Copyleft::of(info),
+                        permissions: &info.permissions,
+                        conditions: &info.conditions,
+                        limitations: &info.limitations,
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&listed).unwrap());
+                return;
+            }
+            if ids {
+                for info in &infos {
+                    println!("{}", info.spdx_id);
+                }
+                return;
+            }
+            if table {
+                let headers: Vec<&str> = columns.iter().map(|column| column.header()).collect();
+                let rows: Vec<Vec<String>> = infos.iter()
+                    .map(|info| columns.iter().map(|column| column.cell(info)).collect())
+                    .collect();
+                print_table(&headers, &rows);
+                return;
+            }
+
+            let grouped = sort == ListSort::Featured && !featured_only;
+            let description_width = terminal_width()
+                .filter(|_| !no_description)
+                .map(|width| width.saturating_sub(LIST_LINE_WIDTH + 3))
+                .filter(|width| *width >= MIN_DESCRIPTION_WIDTH);
+            for (i, info) in infos.iter().enumerate() {
+                let starts_group = i == 0 || is_featured(infos[i - 1]) != is_featured(info);
+                if grouped && starts_group {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("{}", if is_featured(info) { "Featured:" } else { "Other:" });
+                }
+                if !verbose {
+                    let line = format!("{:<60}   -  short:  {:<20}   copyleft:  {:<7}", info.title, info.spdx_id, Copyleft::of(info));
+                    match description_width {
+                        Some(width) => println!("{line}   {}", truncate(&first_sentence(&info.description), width)),
+                        None => println!("{}", line.trim_end()),
+                    }
+                    continue;
+                }
+                println!("{:<60}   -  short:  {:<20}   aliases:  {}", info.title, info.spdx_id, aliases(&info.spdx_id).join(", "));
+                println!("    {}", first_sentence(&info.description));
+                println!("    copyleft:     {}", Copyleft::of(info));
+                println!("    permissions:  {}", info.permissions.join(", "));
+                println!("    conditions:   {}", info.conditions.join(", "));
+                println!("    limitations:  {}", info.limitations.join(", "));
+                if let Some(using) = info.using.as_ref().filter(|using| !using.is_empty()) {
+                    let projects: Vec<&str> = using.keys().map(String::as_str).collect();
+                    println!("    used by:      {}", projects.join(", "));
+                }
+                println!();
+            }
+            if !filters.is_empty() {
+                println!("{} license(s) match", infos.len());
             }
         }
-        Commands::Add { source_root } => {
-            if !preamble_path.exists() {
-                panic!("Run init first");
-            }
-            let source_roots = source_root.unwrap_or_else(|| vec![
-                String::from("src"),
-                String::from("lib")
-            ]);
-            let extensions = vec![
-                ("rs", "//"),
-                ("swift", "//"),
-                ("js", "//"),
-                ("ts", "//"),
-                ("tsx", "//"),
-                ("jsx", "//"),
+        Commands::Languages { check: Some(path) } => {
+            let extension = path.extension().and_then(|s| s.to_str());
+            let file_name = path.file_name().and_then(|s| s.to_str());
+            match extension.and_then(|extension| Some((extension, config.extensions.get(extension)?))) {
+                Some((extension, comment_syntax)) => {
+                    println!("{}: extension {extension}, comment syntax {comment_syntax}", path.display());
+                }
+                None => match file_name.and_then(|file_name| Some((file_name, config.filename_comment_syntax(file_name)?))) {
+                    Some((file_name, comment_syntax)) => {
+                        println!("{}: file name {file_name}, comment syntax {comment_syntax}", path.display());
+                    }
+                    None => {
+                        println!("{}: unsupported", path.display());
+                        std::process::exit(1);
+                    }
+                },
+            }
+        }
+        Commands::Languages { check: None } => {
+            for (extension, comment_syntax) in &config.extensions {
+                let is_built_in = EXTENSIONS.contains(&(extension.as_str(), comment_syntax.as_str()));
+                let origin = if is_built_in { "built-in" } else { "config" };
+                println!("{extension:<10} {comment_syntax:<10} {origin}");
+            }
+            for (file_name, comment_syntax) in FILENAMES {
+                if !config.filenames.contains_key(file_name) {
+                    println!("{file_name:<10} {comment_syntax:<10} built-in");
+                }
+            }
+            for (file_name, comment_syntax) in &config.filenames {
+                println!("{file_name:<10} {comment_syntax:<10} config");
+            }
+        }
+        Commands::Search { terms, json } => {
+            if !search(&licenses, &terms, json) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Export { format: ExportFormat::Json, license } => {
+            let documents: Vec<&Document<LicenseInfo>> = match license {
+                Some(license) => vec![find_license(&licenses, &license)],
+                None => licenses.iter().collect(),
+            };
+            let exported: Vec<ExportedLicense> = documents.iter()
+                .map(|document| ExportedLicense { info: &document.metadata, body: document.content.trim() })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&exported).unwrap());
+        }
+        Commands::Show { license, metadata } => {
+            let license_document = find_license(&licenses, &license);
+            if metadata {
+                let info = &license_document.metadata;
+                println!("title: {}", info.title);
+                println!("spdx-id: {}", info.spdx_id);
+                println!("description: {}", info.description.trim());
+                println!("permissions: {}", info.permissions.join(", "));
+                println!("conditions: {}", info.conditions.join(", "));
+                println!("limitations: {}", info.limitations.join(", "));
+                println!();
+            }
+            println!("{}", license_document.content.trim());
+        }
+        Commands::Info { license } => {
+            let info = &find_license(&licenses, &license).metadata;
+            println!("{} ({})", info.title, info.spdx_id);
+            println!("Copyleft: {}", Copyleft::of(info));
+            println!();
+            println!("{}", info.description.trim());
+            println!();
+            println!("How to apply:");
+            println!("{}", info.how.trim());
+            for (heading, rules) in [
+                ("Permissions", &info.permissions),
+                ("Conditions", &info.conditions),
+                ("Limitations", &info.limitations),
+            ] {
+                println!();
+                println!("{heading}:");
+                for rule in rules {
+                    println!("  - {rule}");
+                }
+            }
+            if let Some(using) = info.using.as_ref().filter(|using| !using.is_empty()) {
+                println!();
+                println!("Used by:");
+                for (project, url) in using {
+                    println!("  - {project}: {url}");
+                }
+            }
+        }
+        Commands::Compare { licenses: names } => {
+            let infos: Vec<&LicenseInfo> = names.iter()
+                .map(|name| &find_license(&licenses, name).metadata)
+                .collect();
+            let widths: Vec<usize> = infos.iter().map(|info| info.spdx_id.len().max(3)).collect();
+
+            type Rules = fn(&LicenseInfo) -> &Vec<String>;
+            let categories: [(&str, Rules); 3] = [
+                ("Permissions", |info| &info.permissions),
+                ("Conditions", |info| &info.conditions),
+                ("Limitations", |info| &info.limitations),
             ];
+            for (index, (heading, rules_of)) in categories.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                let mut line = format!("{heading:<30}");
+                for (info, width) in infos.iter().zip(&widths) {
+                    line += &format!("  {:<width$}", info.spdx_id);
+                }
+                println!("{}", line.trim_end());
+
+                let mut rules: Vec<&String> = vec![];
+                for info in &infos {
+                    for rule in rules_of(info) {
+                        if !rules.contains(&rule) {
+                            rules.push(rule);
+                        }
+                    }
+                }
+                for rule in rules {
+                    let present: Vec<bool> = infos.iter().map(|info| rules_of(info).contains(rule)).collect();
+                    let marker = if present.iter().all(|p| *p) { " " } else { "*" };
+                    let mut line = format!("{marker} {rule:<28}");
+                    for (present, width) in present.iter().zip(&widths) {
+                        line += &format!("  {:<width$}", if *present { "yes" } else { "-" });
+                    }
+                    println!("{}", line.trim_end());
+                }
+            }
+        }
+        Commands::Identify { path } => {
+            let text = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+                eprintln!("Couldn't read {}: {err}", path.display());
+                std::process::exit(1);
+            });
+            match identify(&text, &licenses) {
+                Some((license_document, confidence)) if confidence >= CONFIDENCE_THRESHOLD => {
+                    let info = &license_document.metadata;
+                    println!("{} ({}), confidence {:.0}%", info.title, info.spdx_id, confidence * 100.0);
+                }
+                _ => {
+                    println!("unknown");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::VerifyLicense { fix } => {
+            if !verify_license(&licenses, &config, license_path, fix) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Doctor { source_root } => {
+            if !doctor(&licenses, &config, license_path, preamble_path, source_root) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Notice { holder, year } => {
+            if !notice(&licenses, license_path, notice_path, &holder, year) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Config { command: ConfigCommands::Init { force } } => {
+            let license = project_spdx_id(&licenses, &Config::default(), license_path);
+            if !config_init(config_path, license, force) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Hook { command } => {
+            let hook_path = Path::new(HOOK_PATH);
+            let succeeded = match command {
+                HookCommands::Install => hook_install(hook_path),
+                HookCommands::Uninstall => hook_uninstall(hook_path),
+            };
+            if !succeeded {
+                std::process::exit(1);
+            }
+        }
+        Commands::Man { out_dir } => {
+            write_man_pages(Cli::command(), &out_dir);
+        }
+        Commands::Completions { shell } => {
+            print_completions(shell, Cli::command(), &licenses);
+        }
+        Commands::Suggest { copyleft, permissive, patent_grant, osi_approved, kind } => {
+            let mut criteria = Criteria {
+                copyleft: (copyleft || permissive).then_some(copyleft),
+                patent_grant: patent_grant.then_some(true),
+                osi_approved: osi_approved.then_some(true),
+                kind,
+            };
+            let interactive = criteria.is_empty() && std::io::stdin().is_terminal();
+            if interactive {
+                criteria = Criteria::ask();
+            }
 
-            let preamble_contents = std::fs::read_to_string(preamble_path).unwrap();
+            let mut matches: Vec<&LicenseInfo> = licenses.iter()
+                .map(|document| &document.metadata)
+                .filter(|info| criteria.matches(info))
+                .collect();
+            matches.sort_by_key(|info| !info.featured.unwrap_or(false));
+            if matches.is_empty() {
+                eprintln!("No license matches, try relaxing some of the criteria");
+                std::process::exit(1);
+            }
 
-            for source_root in source_roots {
-                if std::fs::metadata(&source_root).is_err() {
-                    continue;
+            for info in &matches {
+                println!("{:<60}   -  short:  {:}", info.title, info.spdx_id);
+            }
+
+            if interactive {
+                let suggestion = &matches[0].spdx_id;
+                if ask_yes_no(&format!("Initialize the project with {suggestion}?")) == Some(true) {
+                    let placeholders = Placeholders::with_defaults(None, None, None, None, true);
+                    let license_contents = placeholders.fill(find_license(&licenses, suggestion).content.trim());
+                    let header = standard_header(suggestion).map(|header| placeholders.fill(&header));
+                    if !init(
+                        &license_contents,
+                        header.as_deref(),
+                        license_path,
+                        preamble_path,
+                        PreambleLayout::Header,
+                        false,
+                    ) {
+                        std::process::exit(1);
+                    }
+                    warn_long_preamble(&read_preamble(preamble_path), config.max_preamble_lines);
                 }
+            }
+        }
+        Commands::Add {
+            backup,
+            stats_only,
+            spdx,
+            license,
+            copyright,
+            allow_long_preamble,
+            variables,
+            stdin,
+            null,
+            walk,
+            tracked_only,
+            changed_since,
+            staged,
+            force_minified,
+            replace_existing,
+            strict_year,
+            max_file_size,
+            lossy,
+            verbose,
+            source_root,
+        } => {
+            let spdx_id = spdx.then(|| match license {
+                Some(license) => find_license(&licenses, &license).metadata.spdx_id.clone(),
+                None => project_spdx_id(&licenses, &config, license_path).unwrap_or_else(|| {
+                    eprintln!("Couldn't tell the project's license, pass it with --license");
+                    std::process::exit(1);
+                }),
+            });
+            let mut preambles = Preambles::new(&config, preamble_path, &variables);
+            let mut files_per_preamble: BTreeMap<PathBuf, usize> = BTreeMap::new();
 
-                let walk = jwalk::WalkDir::new(source_root);
-                for file in walk {
-                    let file = file.unwrap();
-                    let path = file.path();
-                    if !file.file_type.is_file() {
+            let options = WalkOptions { tracked_only, changed_since, staged, ..walk.options() };
+            let SourceFiles {
+                files,
+                unsupported,
+                ignored,
+                excluded,
+                unused_excludes,
+                depth_pruned,
+                symlinks,
+                vendored,
+                missing,
+            } = select_source_files(&config, source_root, stdin, null, &options);
+            for path in &vendored {
+                eprintln!("Skipping the vendored directory {path:?}, pass --include-vendored to process it");
+            }
+            if verbose {
+                for (path, reason) in &ignored {
+                    eprintln!("Skipping {path:?}, ignored by {reason}");
+                }
+                for path in &excluded {
+                    eprintln!("Skipping {path:?}, excluded");
+                }
+                for path in &symlinks {
+                    eprintln!("Skipping {path:?}, a symbolic link (pass --follow-links to follow it)");
+                }
+                for pattern in &unused_excludes {
+                    eprintln!("Warning: --exclude `{pattern}` didn't match anything");
+                }
+                if depth_pruned > 0 {
+                    eprintln!("Skipped {depth_pruned} director(ies) below --max-depth");
+                }
+            }
+            let excluded = excluded.len();
+            let unsupported = unsupported.len();
+            let scanned = files.len() + unsupported;
+            let max_file_size = max_file_size.unwrap_or(config.max_file_size);
+            let (mut present, mut generated, mut minified, mut not_text, mut too_large) = (0, 0, 0, 0, 0);
+            let (mut outdated, mut foreign, mut opted_out, mut without_preamble, mut errors) = (0, 0, 0, 0, missing);
+            let rules = AddRules { spdx: spdx_id.is_some(), strict_year, force_minified, replace_existing };
+            let mut backup = backup.then(Backup::default);
+            let mut rewrites: Vec<(PathBuf, String)> = vec![];
+            for (path, comment_syntax) in files {
+                let header = match &spdx_id {
+                    Some(spdx_id) => spdx_header(spdx_id, copyright.as_deref(), comment_syntax),
+                    None => {
+                        let Some((file_preamble_path, preamble_contents, is_new)) = preambles.get(&path) else {
+                            eprintln!("No {} found for {path:?}", preamble_path.display());
+                            without_preamble += 1;
+                            continue;
+                        };
+                        if is_new && warn_long_preamble(preamble_contents, config.max_preamble_lines) && !allow_long_preamble {
+                            eprintln!(
+                                "Refusing to add {}, pass --allow-long-preamble to add it anyway",
+                                file_preamble_path.display()
+                            );
+                            std::process::exit(1);
+                        }
+                        *files_per_preamble.entry(file_preamble_path).or_default() += 1;
+                        prefix_preamble(preamble_contents, comment_syntax)
+                    }
+                };
+                if is_too_large(&path, max_file_size) {
+                    eprintln!("Skipping {path:?}, larger than {max_file_size} bytes");
+                    too_large += 1;
+                    continue;
+                }
+                if spdx_id.is_none() && has_preamble_near_top(&path, &header, comment_syntax) {
+                    if !stats_only {
+                        eprintln!("Skipping {path:?}");
+                    }
+                    present += 1;
+                    continue;
+                }
+                let file_contents = match read_source_file(&path, lossy) {
+                    Ok(file_contents) => file_contents,
+                    Err(ReadError::Io(err)) => {
+                        eprintln!("Failed to read {path:?}: {err}");
+                        errors += 1;
+                        continue;
+                    }
+                    Err(err) => {
+                        eprintln!("Warning: skipping {path:?}, {err}");
+                        not_text += 1;
+                        continue;
+                    }
+                };
+                let new_contents = match add_decision(&config, &path, &file_contents, &header, comment_syntax, &rules) {
+                    AddDecision::Add(new_contents) => new_contents,
+                    AddDecision::Replace(new_contents) => {
+                        println!("Replacing the existing header of {path:?}");
+                        new_contents
+                    }
+                    AddDecision::Present => {
+                        if !stats_only {
+                            eprintln!("Skipping {path:?}");
+                        }
+                        present += 1;
+                        continue;
+                    }
+                    AddDecision::Outdated => {
+                        eprintln!("Skipping {path:?}, its preamble is outdated, run `update` to replace it");
+                        outdated += 1;
                         continue;
                     }
+                    AddDecision::Foreign => {
+                        eprintln!("Skipping {path:?}, it has a foreign header (pass --replace-existing to replace it)");
+                        foreign += 1;
+                        continue;
+                    }
+                    AddDecision::OptedOut => {
+                        if verbose {
+                            eprintln!("Skipping {path:?}, opted out with `license-preamble: ignore`");
+                        }
+                        opted_out += 1;
+                        continue;
+                    }
+                    AddDecision::Generated => {
+                        if verbose {
+                            eprintln!("Skipping {path:?}, generated");
+                        }
+                        generated += 1;
+                        continue;
+                    }
+                    AddDecision::InlineHtml => {
+                        eprintln!("Warning: skipping {path:?}, it starts with inline HTML rather than `<?php`");
+                        continue;
+                    }
+                    AddDecision::Minified => {
+                        if !stats_only {
+                            eprintln!("Skipping {path:?}: looks generated/minified");
+                        }
+                        minified += 1;
+                        continue;
+                    }
+                };
+                if let Some(backup) = &mut backup {
+                    backup.record(&path, &file_contents, &new_contents);
+                }
+                rewrites.push((path, new_contents));
+            }
+
+            // The backup is saved before any file is touched so an
+            // interrupted run can still be undone.
+            if let Some(backup) = backup {
+                backup.save(Path::new(BACKUPS_DIR));
+            }
+            let mut added = 0;
+            for (path, new_contents) in rewrites {
+                if !stats_only {
+                    println!("Adding preamble to file {path:?}");
+                }
+                match std::fs::write(&path, new_contents) {
+                    Ok(()) => added += 1,
+                    Err(err) => {
+                        eprintln!("Failed to write {path:?}: {err}");
+                        errors += 1;
+                    }
+                }
+            }
 
-                    if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-                        if let Some((_, comment_syntax)) = extensions.iter().find(|(e, _)| *e == extension) {
-                            let prefixed_preamble = preamble_contents.lines().map(|line| {
-                                format!("{comment_syntax} {line}").trim().to_string()
-                            }).collect::<Vec<String>>().join("\n");
-                            let file_contents = std::fs::read_to_string(&path).unwrap();
-                            if file_contents.contains(&prefixed_preamble) {
-                                eprintln!("Skipping {path:?}");
-                                continue;
-                            }
+            if staged && added > 0 {
+                println!("Stage the modified files again with `git add` to commit the preambles");
+            }
+            println!(
+                "{scanned} file(s) scanned, {added} preamble(s) added, {present} already present, {outdated} outdated, \
+                 {foreign} with a foreign header, {unsupported} unsupported, {excluded} excluded, {generated} generated, \
+                 {minified} minified, {not_text} not text, {too_large} too large, {opted_out} opted out, \
+                 {without_preamble} without a preamble, {errors} error(s)"
+            );
+            for (file_preamble_path, count) in &files_per_preamble {
+                println!("  {count} file(s) under {}", file_preamble_path.display());
+            }
+            if without_preamble > 0 || errors > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Diff { name_only, source_root } => {
+            diff(&config, preamble_path, source_root, name_only);
+        }
+        Commands::Watch { source_root } => {
+            watch(&config, preamble_path, source_root);
+        }
+        Commands::Reuse { check: true, source_root, .. } => {
+            if !reuse_check(&config, source_root) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Reuse { check: false, license, copyright, source_root } => {
+            let spdx_id = match license {
+                Some(license) => find_license(&licenses, &license).metadata.spdx_id.clone(),
+                None => project_spdx_id(&licenses, &config, license_path).unwrap_or_else(|| {
+                    Cli::command()
+                        .error(ErrorKind::MissingRequiredArgument, "couldn't tell the project's license, pass it with --license")
+                        .exit()
+                }),
+            };
+            let copyright = copyright.expect("--copyright is required");
+            reuse(&config, &spdx_id, &copyright, source_root);
+        }
+        Commands::Check {
+            stdin,
+            null,
+            walk,
+            tracked_only,
+            changed_since,
+            staged,
+            strict_year,
+            max_file_size,
+            source_root,
+        } => {
+            let mut preambles = Preambles::new(&config, preamble_path, &[]);
+
+            let options = WalkOptions { tracked_only, changed_since, staged, ..walk.options() };
+            let SourceFiles { files, missing, .. } = select_source_files(&config, source_root, stdin, null, &options);
+            let max_file_size = max_file_size.unwrap_or(config.max_file_size);
+            let (mut failures, mut outdated, mut unreadable) = (0, 0, 0);
+            for (path, comment_syntax) in files {
+                let Some((_, preamble_contents, _)) = preambles.get(&path) else {
+                    eprintln!("No {} found for {path:?}", preamble_path.display());
+                    println!("{}", path.display());
+                    failures += 1;
+                    continue;
+                };
+                let prefixed_preamble = prefix_preamble(preamble_contents, comment_syntax);
+                if !staged && is_too_large(&path, max_file_size) {
+                    eprintln!("Skipping {path:?}, larger than {max_file_size} bytes");
+                    continue;
+                }
+                if !staged && has_preamble_near_top(&path, &prefixed_preamble, comment_syntax) {
+                    continue;
+                }
+                let file_contents = if staged {
+                    staged_contents(&path).unwrap_or_else(|err| {
+                        eprintln!("Couldn't read the staged {path:?}: {err}");
+                        std::process::exit(1);
+                    })
+                } else {
+                    match read_source_file(&path, false) {
+                        Ok(file_contents) => file_contents,
+                        Err(err) => {
+                            eprintln!("Warning: skipping {path:?}, {err}");
+                            unreadable += 1;
+                            continue;
+                        }
+                    }
+                };
+                let is_skipped = has_ignore_directive(&file_contents)
+                    || starts_with_inline_html(&path, &file_contents, comment_syntax)
+                    || is_generated(&config, &file_contents)
+                    || looks_minified(&path, &file_contents);
+                if is_skipped {
+                    continue;
+                }
+                let presence = match &config.markers {
+                    Some(markers) => markers.presence(&file_contents, &prefixed_preamble, comment_syntax),
+                    None => presence(&file_contents, &prefixed_preamble, comment_syntax, strict_year),
+                };
+                match presence {
+                    Presence::Present => {}
+                    Presence::Outdated => {
+                        println!("{}", path.display());
+                        outdated += 1;
+                    }
+                    Presence::Missing => {
+                        println!("{}", path.display());
+                        failures += 1;
+                    }
+                }
+            }
+
+            if outdated > 0 {
+                eprintln!("{outdated} file(s) have an outdated preamble, run `update` to fix them");
+            }
+            if failures > 0 {
+                eprintln!("{failures} file(s) are missing the preamble, run `add` to fix them");
+                if staged {
+                    eprintln!("Files fixed in the working tree need to be staged again with `git add`");
+                }
+            }
+            if unreadable > 0 {
+                eprintln!("{unreadable} file(s) couldn't be checked");
+            }
+            if failures > 0 || outdated > 0 || unreadable > 0 || missing > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Undo { force } => {
+            if !undo(Path::new(BACKUPS_DIR), force) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Status { files, source_root } => {
+            status(&config, preamble_path, source_root, files);
+        }
+        Commands::Remove { walk, source_root } => {
+            let preamble_contents = load_preamble(&config, preamble_path);
 
+            let SourceFiles { files, .. } = select_source_files(&config, source_root, false, false, &walk.options());
+            let mut errors = 0;
+            for (path, comment_syntax) in files {
+                let prefixed_preamble = prefix_preamble(&preamble_contents, comment_syntax);
+                let Some(file_contents) = read_source_file_or_warn(&path) else {
+                    continue;
+                };
+                if has_ignore_directive(&file_contents) {
+                    eprintln!("Skipping {path:?}, opted out with `license-preamble: ignore`");
+                    continue;
+                }
+                if let Some(region) = config.markers.as_ref()
+                    .and_then(|markers| markers.region(&file_contents, comment_syntax))
+                {
+                    let marked_header = &file_contents[region.clone()];
+                    let new_contents = remove_header(&file_contents, marked_header, config.blank_lines, comment_syntax)
+                        .unwrap_or_else(|| {
+                            // The markers aren't at the top, so only the
+                            // region and the blank lines after it go.
+                            let rest = &file_contents[region.end..];
+                            let rest = (0..=config.blank_lines)
+                                .fold(rest, |rest, _| strip_line_ending(rest).unwrap_or(rest));
+                            format!("{}{rest}", &file_contents[..region.start])
+                        });
+                    println!("Removing preamble from file {path:?}");
+                    if !write_source_file(&path, &new_contents) {
+                        errors += 1;
+                    }
+                    continue;
+                }
+                let Some(new_contents) = remove_header(&file_contents, &prefixed_preamble, config.blank_lines, comment_syntax)
+                else {
+                    eprintln!("Skipping {path:?}");
+                    continue;
+                };
+
+                println!("Removing preamble from file {path:?}");
+                if !write_source_file(&path, &new_contents) {
+                    errors += 1;
+                }
+            }
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Migrate { to, fullname, allow_long_preamble, source_root } => {
+            let license_document = find_license(&licenses, &to);
+            if !migrate(license_document, &config, license_path, preamble_path, source_root, fullname, allow_long_preamble) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Normalize { verbose, source_root } => {
+            if !normalize(&config, preamble_path, source_root, verbose) {
+                std::process::exit(1);
+            }
+        }
+        Commands::BumpYear { year, source_root } => {
+            if !bump_year(&config, license_path, preamble_path, source_root, year) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Update { source_root } => {
+            let preamble_contents = load_preamble(&config, preamble_path);
+
+            let (mut updated, mut current, mut opted_out, mut missing, mut errors) = (0, 0, 0, 0, 0);
+            for (path, comment_syntax) in source_files(&config, source_root) {
+                let prefixed_preamble = prefix_preamble(&preamble_contents, comment_syntax);
+                let Some(file_contents) = read_source_file_or_warn(&path) else {
+                    continue;
+                };
+                if has_ignore_directive(&file_contents) {
+                    opted_out += 1;
+                    continue;
+                }
+                if let Some(region) = config.markers.as_ref()
+                    .and_then(|markers| markers.region(&file_contents, comment_syntax))
+                {
+                    if file_contents[region.clone()].replace("\r\n", "\n") == prefixed_preamble {
+                        current += 1;
+                    } else {
+                        println!("Updating preamble in file {path:?}");
+                        let new_contents = format!(
+                            "{}{}{}",
+                            &file_contents[..region.start],
+                            prefixed_preamble.replace('\n', line_ending(&file_contents)),
+                            &file_contents[region.end..],
+                        );
+                        if write_source_file(&path, &new_contents) {
+                            updated += 1;
+                        } else {
+                            errors += 1;
+                        }
+                    }
+                    continue;
+                }
+                let (prologue, body) = split_prologue(&file_contents, comment_syntax);
+                if strip_preamble(body, &prefixed_preamble, config.blank_lines).is_some() {
+                    current += 1;
+                    continue;
+                }
 
-                            println!("Adding preamble to file {path:?}");
-                            let new_contents = format!("{prefixed_preamble}\n\n{file_contents}");
-                            std::fs::write(&path, new_contents).unwrap();
+                match similar_leading_comment(body, &prefixed_preamble, comment_syntax) {
+                    Some((_, rest)) => {
+                        println!("Updating preamble in file {path:?}");
+                        let new_contents = insert_header(
+                            &join_prologue(prologue, rest),
+                            &prefixed_preamble,
+                            config.blank_lines,
+                            comment_syntax,
+                        );
+                        if write_source_file(&path, &new_contents) {
+                            updated += 1;
+                        } else {
+                            errors += 1;
                         }
                     }
+                    None => {
+                        eprintln!("No preamble found in {path:?}");
+                        missing += 1;
+                    }
                 }
             }
+
+            println!(
+                "{updated} updated, {current} already up to date, {opted_out} opted out, {missing} without a preamble, \
+                 {errors} error(s)"
+            );
+            if errors > 0 {
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory for a test's files.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("license-preamble-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn scanning_is_sorted_deduplicated_and_repeatable() {
+        let dir = test_dir("sorted-scan");
+        let file_names = ["b.ts", "a.ts", "z.js", "sub/c.rs", "sub/deeper/d.js", "sub/B.ts"];
+        for file in file_names {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
         }
+        let config = Config::default();
+        // The second root is inside the first, so its files are reachable
+        // twice.
+        let source_roots = vec![dir.join("sub").display().to_string(), dir.display().to_string()];
+        let scan = || -> Vec<PathBuf> {
+            scan_source_roots(&config, Some(source_roots.clone()), &WalkOptions::default())
+                .files
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect()
+        };
+
+        let files = scan();
+        assert_eq!(files, scan());
+        let mut expected: Vec<PathBuf> = file_names.iter().map(|file| dir.join(file)).collect();
+        expected.sort();
+        assert_eq!(files, expected);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// The contents after running `add` on a file, which is unchanged if it
+    /// gets skipped.
+    fn add_once(config: &Config, path: &Path, file_contents: &str, header: &str) -> String {
+        match add_decision(config, path, file_contents, header, "//", &AddRules::default()) {
+            AddDecision::Add(new_contents) | AddDecision::Replace(new_contents) => new_contents,
+            _ => file_contents.to_string(),
+        }
+    }
+
+    #[test]
+    fn add_decision_is_idempotent_on_bom_files() {
+        let config = Config::default();
+        let path = Path::new("src/bom.ts");
+        let header = prefix_preamble("Copyright 2024 Me\nMIT licensed", "//");
+        for file_contents in ["\u{feff}let a = 1;\n", "\u{feff}let a = 1;\r\nlet b = 2;\r\n"] {
+            let once = add_once(&config, path, file_contents, &header);
+            assert!(once.starts_with("\u{feff}// Copyright 2024 Me"));
+            assert_eq!(add_once(&config, path, &once, &header), once);
+            assert_eq!(remove_header(&once, &header, config.blank_lines, "//").as_deref(), Some(file_contents));
+        }
+    }
+
+    #[test]
+    fn link_preamble_copies_license_when_symlinks_fail() {
+        let dir = test_dir("symlink-fallback");
+        let (license_path, preamble_path) = (dir.join("LICENSE"), dir.join("PREAMBLE"));
+        std::fs::write(&license_path, "license text").unwrap();
+
+        link_or_copy(&license_path, &preamble_path, |_, _| Err(std::io::ErrorKind::PermissionDenied.into()));
+
+        assert!(!preamble_path.symlink_metadata().unwrap().is_symlink());
+        assert_eq!(std::fs::read_to_string(&preamble_path).unwrap(), "license text");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn link_preamble_points_at_license_relative_to_preamble() {
+        let dir = test_dir("symlink-relative");
+        std::fs::create_dir(dir.join("docs")).unwrap();
+        let (license_path, preamble_path) = (dir.join("LICENSE"), dir.join("docs").join("PREAMBLE"));
+        std::fs::write(&license_path, "license text").unwrap();
+
+        link_preamble(&license_path, &preamble_path);
+
+        assert_eq!(std::fs::read_link(&preamble_path).unwrap(), Path::new("../LICENSE"));
+        assert_eq!(std::fs::read_to_string(&preamble_path).unwrap(), "license text");
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
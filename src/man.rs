@@ -0,0 +1,47 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Man pages generated from the clap definitions.
+
+use std::path::Path;
+use clap::Command;
+
+/// Writes the page for `command` and one page per subcommand into `out_dir`,
+/// e.g. `license-preamble.1` and `license-preamble-add.1`.
+pub fn write_man_pages(command: Command, out_dir: &Path) {
+    std::fs::create_dir_all(out_dir).unwrap();
+
+    let name = command.get_name().to_string();
+    write_man_page(command.clone(), &out_dir.join(format!("{name}.1")));
+    for subcommand in command.get_subcommands().filter(|subcommand| !subcommand.is_hide_set()) {
+        let page_name = format!("{name}-{}", subcommand.get_name());
+        let page_path = out_dir.join(format!("{page_name}.1"));
+        write_man_page(subcommand.clone().name(page_name), &page_path);
+    }
+}
+
+fn write_man_page(command: Command, page_path: &Path) {
+    let mut page = vec![];
+    clap_mangen::Man::new(command).render(&mut page).unwrap();
+    std::fs::write(page_path, page).unwrap();
+    println!("Wrote {}", page_path.display());
+}
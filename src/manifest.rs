@@ -0,0 +1,143 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reading the declared license and workspace members from package
+//! manifests.
+
+use std::path::{Path, PathBuf};
+
+/// A license expression declared by a manifest.
+pub struct DeclaredLicense {
+    /// The manifest's file name.
+    pub manifest: &'static str,
+    pub expression: String,
+}
+
+/// Reads `package.license` from Cargo.toml, turning the legacy `MIT/Apache-2.0`
+/// form into an SPDX expression.
+fn cargo_license(manifest_path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest_path).ok()?;
+    let manifest: toml::Table = toml::from_str(&contents).unwrap_or_else(|err| {
+        eprintln!("Invalid {}: {err}", manifest_path.display());
+        std::process::exit(1);
+    });
+    let license = manifest.get("package")?.get("license")?.as_str()?;
+    Some(license.split('/').map(str::trim).collect::<Vec<&str>>().join(" OR "))
+}
+
+/// Reads `license` from package.json, or the first entry of the legacy
+/// `licenses` array. Exits with an error for values that don't name a
+/// license, such as `UNLICENSED`.
+fn npm_license(manifest_path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(manifest_path).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&contents).unwrap_or_else(|err| {
+        eprintln!("Invalid {}: {err}", manifest_path.display());
+        std::process::exit(1);
+    });
+
+    let license = match (manifest.get("license"), manifest.get("licenses")) {
+        (Some(license), _) => license.as_str()?.to_string(),
+        (None, Some(licenses)) => {
+            eprintln!("Warning: package.json uses the deprecated `licenses` array, taking its first entry");
+            let first = licenses.as_array()?.first()?;
+            first.as_str().or_else(|| first.get("type")?.as_str())?.to_string()
+        }
+        (None, None) => return None,
+    };
+
+    if license == "UNLICENSED" || license.starts_with("SEE LICENSE IN") {
+        eprintln!("package.json declares the license as {license:?}, which doesn't name a license. Pass a license explicitly");
+        std::process::exit(1);
+    }
+    Some(license)
+}
+
+/// Reads the package name from Cargo.toml or package.json in `dir`.
+pub fn project_name(dir: &Path) -> Option<String> {
+    let cargo_name = std::fs::read_to_string(dir.join("Cargo.toml")).ok()
+        .and_then(|contents| contents.parse::<toml::Table>().ok())
+        .and_then(|manifest| Some(manifest.get("package")?.get("name")?.as_str()?.to_string()));
+    cargo_name.or_else(|| {
+        let contents = std::fs::read_to_string(dir.join("package.json")).ok()?;
+        let manifest: serde_json::Value = serde_json::from_str(&contents).ok()?;
+        Some(manifest.get("name")?.as_str()?.to_string())
+    })
+}
+
+/// Finds the license declared by a manifest in `dir`, preferring
+/// Cargo.toml over package.json.
+pub fn declared_license(dir: &Path) -> Option<DeclaredLicense> {
+    cargo_license(&dir.join("Cargo.toml"))
+        .map(|expression| DeclaredLicense { manifest: "Cargo.toml", expression })
+        .or_else(|| {
+            npm_license(&dir.join("package.json"))
+                .map(|expression| DeclaredLicense { manifest: "package.json", expression })
+        })
+}
+
+/// The directories of a Cargo package holding source files, the ones that
+/// don't exist left out.
+static CARGO_SOURCE_DIRS: [&str; 4] = ["src", "tests", "benches", "examples"];
+
+/// The source directories of every member of the Cargo workspace in `dir`,
+/// and of the root package if there's one, or `None` if Cargo.toml doesn't
+/// declare a `[workspace]`.
+pub fn workspace_source_roots(dir: &Path) -> Option<Vec<String>> {
+    let contents = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = contents.parse().ok()?;
+    let workspace = manifest.get("workspace")?;
+    let patterns = |key: &str| -> Vec<&str> {
+        workspace.get(key)
+            .and_then(|patterns| patterns.as_array())
+            .map(|patterns| patterns.iter().filter_map(|pattern| pattern.as_str()).collect())
+            .unwrap_or_default()
+    };
+
+    let excluded: Vec<PathBuf> = patterns("exclude").iter()
+        .flat_map(|pattern| expand_member(dir, pattern))
+        .collect();
+    let mut members: Vec<PathBuf> = patterns("members").iter()
+        .flat_map(|pattern| expand_member(dir, pattern))
+        .filter(|member| !excluded.contains(member) && member.join("Cargo.toml").is_file())
+        .collect();
+    if manifest.contains_key("package") {
+        members.push(dir.to_path_buf());
+    }
+    members.sort();
+    members.dedup();
+
+    let source_roots = members.iter()
+        .flat_map(|member| CARGO_SOURCE_DIRS.iter().map(move |source_dir| member.join(source_dir)))
+        .filter(|source_root| source_root.is_dir())
+        .map(|source_root| source_root.to_string_lossy().into_owned())
+        .collect();
+    Some(source_roots)
+}
+
+/// The directories matching a workspace member pattern such as `crates/*`.
+fn expand_member(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let Ok(paths) = glob::glob(&dir.join(pattern).to_string_lossy()) else {
+        eprintln!("Warning: invalid workspace member pattern `{pattern}` in Cargo.toml");
+        return vec![];
+    };
+    paths.filter_map(Result::ok).filter(|path| path.is_dir()).collect()
+}
@@ -0,0 +1,103 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! BEGIN/END marker comments around the preamble.
+
+use std::ops::Range;
+use serde::{Deserialize, Serialize};
+
+use crate::preamble::{block_tokens, Presence};
+
+/// The marker lines put around the preamble when `[markers]` is set in the
+/// configuration file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Markers {
+    pub begin: String,
+    pub end: String,
+}
+
+impl Default for Markers {
+    fn default() -> Self {
+        Markers {
+            begin: String::from("--- BEGIN LICENSE PREAMBLE ---"),
+            end: String::from("--- END LICENSE PREAMBLE ---"),
+        }
+    }
+}
+
+impl Markers {
+    /// Puts the marker lines before and after the rendered preamble.
+    pub fn wrap(&self, preamble_contents: &str) -> String {
+        format!("{}\n{}\n{}", self.begin, preamble_contents.trim_end(), self.end)
+    }
+
+    /// The byte range of the marked preamble in a file, from the start of
+    /// the begin marker's line to the end of the end marker's line. For
+    /// block comment syntaxes the lines opening and closing the comment
+    /// are included.
+    pub fn region(&self, file_contents: &str, comment_syntax: &str) -> Option<Range<usize>> {
+        let begin = file_contents.find(&self.begin)?;
+        let end = begin + file_contents[begin..].find(&self.end)? + self.end.len();
+        let mut start = line_start(file_contents, begin);
+        let mut end = line_end(file_contents, end);
+
+        if let Some((open, close)) = block_tokens(comment_syntax) {
+            if start > 0 {
+                let previous_start = line_start(file_contents, start - 1);
+                if file_contents[previous_start..start].trim() == open {
+                    start = previous_start;
+                }
+            }
+            if let Some(next_start) = file_contents[end..].find('\n').map(|i| end + i + 1) {
+                let next_end = line_end(file_contents, next_start);
+                if file_contents[next_start..next_end].trim() == close {
+                    end = next_end;
+                }
+            }
+        }
+        Some(start..end)
+    }
+
+    /// Whether the marked region holds exactly the preamble, is there with
+    /// other contents, or is missing.
+    pub fn presence(&self, file_contents: &str, prefixed_preamble: &str, comment_syntax: &str) -> Presence {
+        match self.region(file_contents, comment_syntax) {
+            Some(region) if file_contents[region.clone()].replace("\r\n", "\n") == prefixed_preamble => Presence::Present,
+            Some(_) => Presence::Outdated,
+            None => Presence::Missing,
+        }
+    }
+}
+
+/// The offset of the start of the line holding `offset`.
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The offset of the end of the line holding `offset`, before its line
+/// ending.
+fn line_end(text: &str, offset: usize) -> usize {
+    let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    if text[..end].ends_with('\r') { end - 1 } else { end }
+}
@@ -0,0 +1,129 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Switching a project from one license to another.
+
+use std::path::{Path, PathBuf};
+use yaml_front_matter::Document;
+
+use crate::config::Config;
+use crate::headers::standard_header;
+use crate::license::LicenseInfo;
+use crate::placeholders::{unfilled, Placeholders};
+use crate::preamble::{insert_header, is_similar, join_prologue, prefix_preamble, split_leading_comment, split_prologue, strip_preamble};
+use crate::template::render_preamble;
+use crate::{load_preamble, read_source_file_or_warn, source_files, warn_long_preamble, write_source_file};
+
+/// Replaces LICENSE, PREAMBLE and the preamble of every source file with the
+/// new license, whose placeholders are filled in like with `init`, the
+/// copyright holder being `fullname` or else the configured one. A PREAMBLE
+/// symlinked to LICENSE stays one, the files then getting the whole new
+/// license. Nothing is written unless the old preamble can be found in every
+/// file, otherwise the offending files are listed and `false` is returned.
+/// A new preamble with unfilled placeholders is refused, and so is one
+/// longer than `max-preamble-lines` unless `allow_long_preamble` is set.
+pub fn migrate(
+    license_document: &Document<LicenseInfo>,
+    config: &Config,
+    license_path: &Path,
+    preamble_path: &Path,
+    source_root: Option<Vec<String>>,
+    fullname: Option<String>,
+    allow_long_preamble: bool,
+) -> bool {
+    let old_preamble_contents = load_preamble(config, preamble_path);
+    let placeholders = Placeholders::with_defaults(fullname.or_else(|| config.holder.clone()), None, None, None, true);
+    let license_contents = placeholders.fill(license_document.content.trim());
+    let preamble_is_symlink = preamble_path.symlink_metadata().is_ok_and(|metadata| metadata.is_symlink());
+    let new_preamble_template = match standard_header(&license_document.metadata.spdx_id) {
+        Some(header) if !preamble_is_symlink => placeholders.fill(&header),
+        _ => license_contents.clone(),
+    };
+    let unfilled = unfilled(&new_preamble_template);
+    if !unfilled.is_empty() {
+        eprintln!("Refusing to migrate, the new preamble would keep {} unfilled, pass --fullname", unfilled.join(", "));
+        return false;
+    }
+    if warn_long_preamble(&new_preamble_template, config.max_preamble_lines) && !allow_long_preamble {
+        eprintln!("Refusing to migrate, pass --allow-long-preamble to migrate anyway");
+        return false;
+    }
+    let new_preamble_contents = render_preamble(config, &new_preamble_template, &[]);
+
+    let mut rewrites: Vec<(PathBuf, String)> = vec![];
+    let mut unidentified: Vec<PathBuf> = vec![];
+    for (path, comment_syntax) in source_files(config, source_root) {
+        let old_prefixed_preamble = prefix_preamble(&old_preamble_contents, comment_syntax);
+        let new_prefixed_preamble = prefix_preamble(&new_preamble_contents, comment_syntax);
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        let (prologue, body) = split_prologue(&file_contents, comment_syntax);
+        if strip_preamble(body, &new_prefixed_preamble, config.blank_lines).is_some() {
+            continue;
+        }
+
+        let rest = strip_preamble(body, &old_prefixed_preamble, config.blank_lines).or_else(|| {
+            split_leading_comment(body, comment_syntax)
+                .filter(|(header, _)| is_similar(header, &old_prefixed_preamble, comment_syntax))
+                .map(|(_, rest)| rest)
+        });
+        match rest {
+            Some(rest) => {
+                let new_contents = insert_header(
+                    &join_prologue(prologue, rest),
+                    &new_prefixed_preamble,
+                    config.blank_lines,
+                    comment_syntax,
+                );
+                rewrites.push((path, new_contents));
+            }
+            None => unidentified.push(path),
+        }
+    }
+
+    if !unidentified.is_empty() {
+        eprintln!("Refusing to migrate, the old preamble couldn't be found in:");
+        for path in unidentified {
+            eprintln!("{}", path.display());
+        }
+        return false;
+    }
+
+    // The files are left alone if LICENSE or PREAMBLE can't be written.
+    if !write_source_file(license_path, &license_contents) {
+        return false;
+    }
+    if !preamble_is_symlink && !write_source_file(preamble_path, &new_preamble_template) {
+        return false;
+    }
+    println!("Switched LICENSE and PREAMBLE to the {}", license_document.metadata.title);
+
+    let mut errors = 0;
+    for (path, new_contents) in rewrites {
+        println!("Migrating preamble in file {path:?}");
+        if !write_source_file(&path, &new_contents) {
+            errors += 1;
+        }
+    }
+    errors == 0
+}
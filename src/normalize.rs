@@ -0,0 +1,82 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Rewriting hand-written preambles to the exact text `add` produces.
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::preamble::{insert_header, join_prologue, prefix_preamble, similar_leading_comment, split_prologue, strip_preamble};
+use crate::{load_preamble, read_source_file_or_warn, source_files, write_source_file};
+
+/// Rewrites every leading comment block that reads like the preamble, e.g.
+/// with different spacing, trailing whitespace or another comment style, to
+/// the preamble `add` would insert. A preamble with CRLF line endings counts
+/// as normalized, as `add` keeps the line endings of the file. With
+/// `verbose`, the changed lines are printed for each file. Returns whether
+/// every file could be written.
+pub fn normalize(config: &Config, preamble_path: &Path, source_root: Option<Vec<String>>, verbose: bool) -> bool {
+    let preamble_contents = load_preamble(config, preamble_path);
+
+    let (mut normalized, mut current, mut errors) = (0, 0, 0);
+    for (path, comment_syntax) in source_files(config, source_root) {
+        let prefixed_preamble = prefix_preamble(&preamble_contents, comment_syntax);
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        let (prologue, body) = split_prologue(&file_contents, comment_syntax);
+        if strip_preamble(body, &prefixed_preamble, config.blank_lines).is_some() {
+            current += 1;
+            continue;
+        }
+
+        let Some((header, rest)) = similar_leading_comment(body, &prefixed_preamble, comment_syntax) else {
+            continue;
+        };
+
+        println!("Normalizing preamble in file {path:?}");
+        if verbose {
+            for (old, new) in header.split('\n').zip(prefixed_preamble.lines()) {
+                if old != new {
+                    println!("  - {old:?}");
+                    println!("  + {new:?}");
+                }
+            }
+            let (old_count, new_count) = (header.split('\n').count(), prefixed_preamble.lines().count());
+            for old in header.split('\n').skip(new_count) {
+                println!("  - {old:?}");
+            }
+            for new in prefixed_preamble.lines().skip(old_count) {
+                println!("  + {new:?}");
+            }
+        }
+        let new_contents = insert_header(&join_prologue(prologue, rest), &prefixed_preamble, config.blank_lines, comment_syntax);
+        if write_source_file(&path, &new_contents) {
+            normalized += 1;
+        } else {
+            errors += 1;
+        }
+    }
+
+    println!("{normalized} normalized, {current} already normalized, {errors} error(s)");
+    errors == 0
+}
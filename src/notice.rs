@@ -0,0 +1,66 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The NOTICE file accompanying Apache-2.0 licensed projects.
+
+use std::path::Path;
+use yaml_front_matter::Document;
+
+use crate::identify::{identify, CONFIDENCE_THRESHOLD};
+use crate::license::LicenseInfo;
+
+/// The license whose projects conventionally ship a NOTICE file.
+const APACHE_SPDX_ID: &str = "Apache-2.0";
+
+/// Writes the NOTICE file attributing the project to `holder`, returning
+/// `false` if it already exists. Warns when LICENSE isn't Apache-2.0.
+pub fn notice(
+    licenses: &[Document<LicenseInfo>],
+    license_path: &Path,
+    notice_path: &Path,
+    holder: &str,
+    year: u16,
+) -> bool {
+    if notice_path.exists() {
+        eprintln!("Refusing to overwrite NOTICE file");
+        return false;
+    }
+
+    let is_apache = std::fs::read_to_string(license_path).ok()
+        .and_then(|license_contents| identify(&license_contents, licenses))
+        .is_some_and(|(license_document, confidence)| {
+            confidence >= CONFIDENCE_THRESHOLD && license_document.metadata.spdx_id == APACHE_SPDX_ID
+        });
+    if !is_apache {
+        eprintln!("Warning: LICENSE isn't the Apache License 2.0, NOTICE files are specific to it");
+    }
+
+    let project = std::env::current_dir().ok()
+        .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| holder.to_string());
+    let contents = format!(
+        "{project}\nCopyright {year} {holder}\n\nThis product includes software developed at\n{holder}.\n"
+    );
+    std::fs::write(notice_path, contents).unwrap();
+    println!("Wrote {}", notice_path.display());
+    true
+}
@@ -0,0 +1,86 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Picking a license interactively for `init`.
+
+use yaml_front_matter::Document;
+
+use crate::license::LicenseInfo;
+use crate::suggest::{ask_text, ask_yes_no};
+
+/// Lists the licenses, featured ones first, and asks for one until a choice
+/// is confirmed. Typing text instead of a number narrows the list down to
+/// the licenses whose title or SPDX id contains it.
+pub fn pick_license(licenses: &[Document<LicenseInfo>]) -> &Document<LicenseInfo> {
+    let mut all: Vec<&Document<LicenseInfo>> = licenses.iter().collect();
+    all.sort_by_key(|document| !document.metadata.featured.unwrap_or(false));
+
+    let mut candidates = all.clone();
+    loop {
+        for (index, document) in candidates.iter().enumerate() {
+            let info = &document.metadata;
+            println!("{:>3}. {:<60}   -  short:  {:}", index + 1, info.title, info.spdx_id);
+        }
+
+        let answer = ask_text("Pick a license by number, or type to filter (empty to show all):");
+        if let Ok(number) = answer.parse::<usize>() {
+            match number.checked_sub(1).and_then(|index| candidates.get(index)) {
+                Some(document) => {
+                    if ask_yes_no(&format!("Use the {}?", document.metadata.title)) == Some(true) {
+                        return document;
+                    }
+                }
+                None => eprintln!("There's no license numbered {number}"),
+            }
+            continue;
+        }
+
+        let filter = answer.to_lowercase();
+        let filtered: Vec<&Document<LicenseInfo>> = all.iter()
+            .filter(|document| {
+                let info = &document.metadata;
+                info.title.to_lowercase().contains(&filter) || info.spdx_id.to_lowercase().contains(&filter)
+            })
+            .copied()
+            .collect();
+        if filtered.is_empty() {
+            eprintln!("No license matches {answer:?}");
+            candidates = all.clone();
+        } else {
+            candidates = filtered;
+        }
+    }
+}
+
+/// Asks for a value, keeping `default` when the answer is empty.
+pub fn ask_with_default(question: &str, default: Option<String>) -> Option<String> {
+    let question = match &default {
+        Some(default) => format!("{question} [{default}]"),
+        None => question.to_string(),
+    };
+    let answer = ask_text(&question);
+    if answer.is_empty() {
+        default
+    } else {
+        Some(answer)
+    }
+}
@@ -0,0 +1,108 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Filling in the `[placeholder]`s of the license templates.
+
+use crate::bump_year::current_year;
+
+/// The placeholders used by the choosealicense.com templates.
+const KNOWN_PLACEHOLDERS: [&str; 7] = ["fullname", "login", "email", "project", "description", "year", "projecturl"];
+
+/// Values for the placeholders, left unfilled when `None`.
+#[derive(Default)]
+pub struct Placeholders {
+    pub fullname: Option<String>,
+    pub year: Option<u16>,
+    pub email: Option<String>,
+    pub project: Option<String>,
+}
+
+impl Placeholders {
+    /// Takes the given values, defaulting the year to the current one and,
+    /// unless `use_git` is unset, the full name and email to git's
+    /// `user.name` and `user.email`.
+    pub fn with_defaults(
+        fullname: Option<String>,
+        year: Option<u16>,
+        email: Option<String>,
+        project: Option<String>,
+        use_git: bool,
+    ) -> Placeholders {
+        Placeholders {
+            fullname: fullname.or_else(|| use_git.then(|| git_config("user.name")).flatten()),
+            year: Some(year.unwrap_or_else(current_year)),
+            email: email.or_else(|| use_git.then(|| git_config("user.email")).flatten()),
+            project,
+        }
+    }
+
+    /// Prints the values so they can be checked.
+    pub fn print(&self) {
+        let values = [
+            ("Copyright holder", self.fullname.clone()),
+            ("Year", self.year.map(|year| year.to_string())),
+            ("Email", self.email.clone()),
+            ("Project", self.project.clone()),
+        ];
+        for (name, value) in values {
+            println!("{name}: {}", value.as_deref().unwrap_or("(unfilled)"));
+        }
+    }
+
+    /// Replaces the placeholders that have a value, warning about the known
+    /// ones that remain.
+    pub fn fill(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        let values = [
+            ("fullname", self.fullname.clone()),
+            ("year", self.year.map(|year| year.to_string())),
+            ("email", self.email.clone()),
+            ("project", self.project.clone()),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                text = text.replace(&format!("[{name}]"), &value);
+            }
+        }
+
+        let unfilled = unfilled(&text);
+        if !unfilled.is_empty() {
+            eprintln!("Warning: left {} unfilled, edit them by hand", unfilled.join(", "));
+        }
+        text
+    }
+}
+
+/// The known placeholders left in `text`.
+pub fn unfilled(text: &str) -> Vec<String> {
+    KNOWN_PLACEHOLDERS.iter()
+        .map(|name| format!("[{name}]"))
+        .filter(|placeholder| text.contains(placeholder.as_str()))
+        .collect()
+}
+
+/// Reads a value from git's configuration, if git is available and it's set.
+pub fn git_config(key: &str) -> Option<String> {
+    let output = std::process::Command::new("git").args(["config", "--get", key]).output().ok()?;
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
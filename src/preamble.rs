@@ -0,0 +1,607 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Rendering and detection of the commented preamble inside source files.
+
+use std::path::Path;
+
+use crate::style::{equivalent_syntaxes, is_rule};
+
+/// Preambles longer than this many lines are flagged as too long to be
+/// copied into every file.
+pub const LONG_PREAMBLE_LINES: usize = 60;
+
+/// Minimum similarity for a leading comment block to be considered an older
+/// version of the preamble.
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// The opening and closing tokens of a block comment syntax, written with a
+/// space between them such as `<!-- -->`, or `None` for line comments.
+pub fn block_tokens(comment_syntax: &str) -> Option<(&str, &str)> {
+    let (open, rest) = comment_syntax.split_once(' ')?;
+    Some((open, rest.rsplit(' ').next().unwrap_or(rest)))
+}
+
+/// The token put in front of every line of a block comment, written between
+/// the opening and closing ones such as the `*` of `/* * */`.
+fn block_line_prefix(comment_syntax: &str) -> Option<&str> {
+    let mut tokens = comment_syntax.split(' ');
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(_), Some(prefix), Some(_)) => Some(prefix),
+        _ => None,
+    }
+}
+
+/// Prefixes every line of the preamble with the comment syntax, or wraps it
+/// in a block comment for block comment syntaxes.
+pub fn prefix_preamble(preamble_contents: &str, comment_syntax: &str) -> String {
+    if let Some((open, close)) = block_tokens(comment_syntax) {
+        if let Some(prefix) = block_line_prefix(comment_syntax) {
+            let lines = preamble_contents.lines().map(|line| {
+                format!(" {prefix} {line}").trim_end().to_string()
+            }).collect::<Vec<String>>().join("\n");
+            return format!("{open}\n{lines}\n {close}");
+        }
+        let lines = preamble_contents.lines().map(str::trim_end).collect::<Vec<&str>>().join("\n");
+        return format!("{open}\n{lines}\n{close}");
+    }
+    preamble_contents.lines().map(|line| {
+        format!("{comment_syntax} {line}").trim().to_string()
+    }).collect::<Vec<String>>().join("\n")
+}
+
+/// Strips one line ending from the start of `text`.
+pub fn strip_line_ending(text: &str) -> Option<&str> {
+    text.strip_prefix("\r\n").or_else(|| text.strip_prefix('\n'))
+}
+
+/// Returns the file contents without the leading preamble block that `add`
+/// inserts, along with up to `blank_lines` blank lines after it, or `None`
+/// if the file doesn't start with it. The preamble may have either line
+/// ending.
+pub fn strip_preamble<'a>(file_contents: &'a str, prefixed_preamble: &str, blank_lines: usize) -> Option<&'a str> {
+    let rest = file_contents.strip_prefix(prefixed_preamble)
+        .or_else(|| file_contents.strip_prefix(prefixed_preamble.replace('\n', "\r\n").as_str()))?;
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    let mut rest = strip_line_ending(rest)?;
+    for _ in 0..blank_lines {
+        match strip_line_ending(rest) {
+            Some(stripped) => rest = stripped,
+            None => break,
+        }
+    }
+    Some(rest)
+}
+
+/// Splits the contiguous comment block at the top of the file from the rest
+/// of the contents, dropping the blank line that separates them.
+pub fn split_leading_comment<'a>(file_contents: &'a str, comment_syntax: &str) -> Option<(&'a str, &'a str)> {
+    if let Some((open, close)) = block_tokens(comment_syntax) {
+        if !file_contents.starts_with(open) {
+            return None;
+        }
+        let end = file_contents.find(close)? + close.len();
+        let rest = &file_contents[end..];
+        let rest = rest.strip_prefix('\n').unwrap_or(rest);
+        return Some((&file_contents[..end], rest.strip_prefix('\n').unwrap_or(rest)));
+    }
+
+    let mut end = 0;
+    for line in file_contents.split_inclusive('\n') {
+        if !line.trim_start().starts_with(comment_syntax) {
+            break;
+        }
+        end += line.len();
+    }
+    if end == 0 {
+        return None;
+    }
+
+    let header = file_contents[..end].trim_end_matches('\n');
+    let rest = &file_contents[end..];
+    Some((header, rest.strip_prefix('\n').unwrap_or(rest)))
+}
+
+/// Strips comment tokens and decorative rules and collapses whitespace so
+/// headers can be compared by their text alone.
+fn normalize(header: &str, comment_syntax: &str) -> String {
+    if let Some((open, close)) = block_tokens(comment_syntax) {
+        let header = header.replace(open, " ").replace(close, " ");
+        let prefix = block_line_prefix(comment_syntax);
+        return header.lines().map(|line| {
+            let line = line.trim_start();
+            prefix.and_then(|prefix| line.strip_prefix(prefix)).unwrap_or(line)
+        }).filter(|line| !is_rule(line)).flat_map(str::split_whitespace).collect::<Vec<&str>>().join(" ");
+    }
+    header.lines().map(|line| {
+        let line = line.trim_start();
+        line.strip_prefix(comment_syntax).unwrap_or(line)
+    }).filter(|line| !is_rule(line)).flat_map(str::split_whitespace).collect::<Vec<&str>>().join(" ")
+}
+
+/// Whether `header` looks like a (possibly outdated) rendering of the
+/// `prefixed_preamble`, e.g. one with a different copyright year.
+pub fn is_similar(header: &str, prefixed_preamble: &str, comment_syntax: &str) -> bool {
+    is_similar_text(&normalize(header, comment_syntax), &normalize(prefixed_preamble, comment_syntax))
+}
+
+/// Whether two normalized texts are close enough for one to be an older
+/// version of the other.
+fn is_similar_text(header_text: &str, preamble_text: &str) -> bool {
+    strsim::sorensen_dice(header_text, preamble_text) >= SIMILARITY_THRESHOLD
+}
+
+/// Whether a file has the preamble.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    /// The preamble is there, maybe with different whitespace or wrapping.
+    Present,
+    /// The leading comment is an older version of the preamble, for `update`
+    /// to replace.
+    Outdated,
+    Missing,
+}
+
+/// Whether `text` has a four digit year at its start.
+fn starts_with_year(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 4 && bytes[..4].iter().all(u8::is_ascii_digit) && !bytes.get(4).is_some_and(u8::is_ascii_digit)
+}
+
+/// Replaces the years and year ranges such as `2019-2024` in `text` with a
+/// placeholder, so that texts differing only in their copyright years
+/// compare equal.
+fn mask_years(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = (0..rest.len()).find(|&index| {
+        rest.is_char_boundary(index)
+            && !rest[..index].ends_with(|c: char| c.is_ascii_digit())
+            && starts_with_year(&rest[index..])
+    }) {
+        masked.push_str(&rest[..start]);
+        masked.push_str("{year}");
+        rest = &rest[start + 4..];
+        let range_end = rest.trim_start()
+            .strip_prefix(['-', '–'])
+            .map(str::trim_start)
+            .filter(|end| starts_with_year(end));
+        if let Some(range_end) = range_end {
+            rest = &range_end[4..];
+        }
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// Looks for the preamble in a file, comparing its leading comments by
+/// their text alone so that differences in whitespace or wrapping don't
+/// count, and neither do the copyright years unless `strict_year` is set.
+/// The leading comments are read in every equivalent comment syntax, so a
+/// preamble added as `//` lines is found when block comments are used.
+pub fn presence(file_contents: &str, prefixed_preamble: &str, comment_syntax: &str, strict_year: bool) -> Presence {
+    if contains_preamble(file_contents, prefixed_preamble, comment_syntax) {
+        return Presence::Present;
+    }
+    let mut preamble_text = normalize(prefixed_preamble, comment_syntax);
+    if !strict_year {
+        preamble_text = mask_years(&preamble_text);
+    }
+    for syntax in equivalent_syntaxes(comment_syntax) {
+        let mut leading_text = normalize(leading_comments(file_contents, syntax), syntax);
+        if !strict_year {
+            leading_text = mask_years(&leading_text);
+        }
+        if !preamble_text.is_empty() && leading_text.contains(&preamble_text) {
+            return Presence::Present;
+        }
+    }
+    match similar_leading_comment(split_prologue(file_contents, comment_syntax).1, prefixed_preamble, comment_syntax) {
+        Some(_) => Presence::Outdated,
+        None => Presence::Missing,
+    }
+}
+
+/// Splits the leading comment from the rest of `body` like
+/// [`split_leading_comment`] if it reads like the preamble, looking for it
+/// in every equivalent comment syntax so that it's found after switching
+/// styles.
+pub fn similar_leading_comment<'a>(
+    body: &'a str,
+    prefixed_preamble: &str,
+    comment_syntax: &str,
+) -> Option<(&'a str, &'a str)> {
+    let preamble_text = normalize(prefixed_preamble, comment_syntax);
+    equivalent_syntaxes(comment_syntax).into_iter().find_map(|syntax| {
+        split_leading_comment(body, syntax)
+            .filter(|(header, _)| is_similar_text(&normalize(header, syntax), &preamble_text))
+    })
+}
+
+/// Whether `header` reads like a copyright or license notice.
+pub fn looks_like_notice(header: &str, comment_syntax: &str) -> bool {
+    let text = normalize(header, comment_syntax).to_lowercase();
+    ["copyright", "(c)", "©", "license", "spdx-license-identifier", "all rights reserved"]
+        .iter()
+        .any(|keyword| text.contains(keyword))
+}
+
+/// The tag marking SPDX license headers.
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+
+/// Renders the one-line SPDX header, optionally followed by a copyright
+/// line.
+pub fn spdx_header(spdx_id: &str, copyright: Option<&str>, comment_syntax: &str) -> String {
+    let mut header = format!("{SPDX_TAG} {spdx_id}");
+    if let Some(copyright) = copyright {
+        header += &format!("\nCopyright (c) {copyright}");
+    }
+    prefix_preamble(&header, comment_syntax)
+}
+
+/// Whether the file carries an SPDX header, whatever its comment syntax.
+pub fn has_spdx_header(file_contents: &str) -> bool {
+    file_contents.lines().any(|line| line.contains(SPDX_TAG))
+}
+
+/// Whether `line` is a `#!` line naming the program that runs the file.
+/// Rust's `#![...]` inner attributes start the same way but aren't one.
+fn is_shebang(line: &str) -> bool {
+    line.strip_prefix("#!").is_some_and(|rest| !rest.trim_start().starts_with('['))
+}
+
+/// Whether files in `comment_syntax` can declare their encoding on their
+/// first or second line, as Python and Ruby files do.
+fn has_coding_lines(comment_syntax: &str) -> bool {
+    comment_syntax == "#"
+}
+
+/// Whether `line` is a PEP 263 encoding declaration such as `# -*- coding:
+/// utf-8 -*-`, matching `^[ \t\f]*#.*?coding[:=][ \t]*[-\w.]+`.
+fn is_coding_line(line: &str) -> bool {
+    let Some(comment) = line.trim_start_matches([' ', '\t', '\x0c']).strip_prefix('#') else {
+        return false;
+    };
+    comment.match_indices("coding").any(|(start, _)| {
+        let rest = &comment[start + "coding".len()..];
+        rest.strip_prefix([':', '='])
+            .map(|rest| rest.trim_start_matches([' ', '\t']))
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    })
+}
+
+/// The line opening and closing the YAML front matter of Markdown files.
+const FRONT_MATTER_FENCE: &str = "---";
+
+/// Whether files in `comment_syntax` can start with YAML front matter, as
+/// Markdown files do. In YAML itself `---` starts a document instead.
+fn has_front_matter(comment_syntax: &str) -> bool {
+    comment_syntax == "<!-- -->"
+}
+
+/// The byte order mark some editors start files with.
+const BOM: char = '\u{feff}';
+
+/// Whether `line` starts a `<!DOCTYPE ...>`, whatever its case, which has to
+/// come before any comment in HTML to keep browsers out of quirks mode.
+fn is_doctype(line: &str) -> bool {
+    let line = line.trim_start();
+    line.get(.."<!doctype".len()).is_some_and(|start| start.eq_ignore_ascii_case("<!doctype"))
+}
+
+/// Splits the lines that have to stay first from the rest of the file,
+/// dropping the blank lines after them: a byte order mark, the `#!` line for
+/// the file to run, an encoding declaration on the first or second line in
+/// languages that have them, PHP's `<?php` opening tag, XML's `<?xml ...?>` declaration and a
+/// `<!DOCTYPE ...>`, and the YAML front matter of Markdown files. The first
+/// part is empty for files without any of them.
+pub fn split_prologue<'a>(file_contents: &'a str, comment_syntax: &str) -> (&'a str, &'a str) {
+    // A byte order mark stays the very first thing in the file.
+    let start = if file_contents.starts_with(BOM) { BOM.len_utf8() } else { 0 };
+    let mut lines = file_contents[start..].split_inclusive('\n').peekable();
+    let mut end = start;
+    if let Some(line) = lines.next_if(|line| is_shebang(line)) {
+        end += line.len();
+    }
+    if let Some(line) = lines.next_if(|line| has_coding_lines(comment_syntax) && is_coding_line(line)) {
+        end += line.len();
+    }
+    if let Some(line) = lines.next_if(|line| line.starts_with("<?php")) {
+        end += line.len();
+    }
+    let opens_front_matter = |line: &&str| has_front_matter(comment_syntax) && line.trim_end() == FRONT_MATTER_FENCE;
+    if end == start && lines.peek().is_some_and(opens_front_matter) {
+        // Front matter is only taken when its closing fence is found.
+        let closing = file_contents[start..].split_inclusive('\n')
+            .skip(1)
+            .position(|line| line.trim_end() == FRONT_MATTER_FENCE);
+        if let Some(closing) = closing {
+            end += lines.by_ref().take(closing + 2).map(str::len).sum::<usize>();
+        }
+    } else if end == start {
+        if let Some(line) = lines.next_if(|line| line.starts_with("<?xml")) {
+            end += line.len();
+        }
+        if lines.peek().is_some_and(|line| is_doctype(line)) {
+            // The DOCTYPE can span several lines with an internal subset
+            // between brackets.
+            let terminator = if lines.peek().is_some_and(|line| line.contains('[')) { "]>" } else { ">" };
+            for line in lines.by_ref() {
+                end += line.len();
+                if line.contains(terminator) {
+                    break;
+                }
+            }
+        }
+    }
+    let (prologue, rest) = file_contents.split_at(end);
+    if end == start {
+        return (prologue, rest);
+    }
+    (prologue, rest.trim_start_matches(['\r', '\n']))
+}
+
+/// Whether `path` is a PHP file starting with inline HTML rather than a
+/// `<?php` tag, where a commented preamble would be output as text.
+pub fn starts_with_inline_html(path: &Path, file_contents: &str, comment_syntax: &str) -> bool {
+    path.extension().is_some_and(|extension| extension == "php")
+        && !file_contents.trim().is_empty()
+        && !split_prologue(file_contents, comment_syntax).0.contains("<?php")
+}
+
+/// The line ending most of the lines of `text` use, `\r\n` or `\n`.
+pub fn line_ending(text: &str) -> &'static str {
+    let crlf = text.matches("\r\n").count();
+    if crlf > text.matches('\n').count() - crlf { "\r\n" } else { "\n" }
+}
+
+/// The run of comments and blank lines at the top of the file, after the
+/// lines that have to stay first.
+fn leading_comments<'a>(file_contents: &'a str, comment_syntax: &str) -> &'a str {
+    let body = split_prologue(file_contents, comment_syntax).1;
+    let end = match block_tokens(comment_syntax) {
+        Some((open, close)) => {
+            let mut end = 0;
+            loop {
+                let rest = &body[end..];
+                let trimmed = rest.trim_start();
+                if !trimmed.starts_with(open) {
+                    break end;
+                }
+                let start = end + rest.len() - trimmed.len();
+                match body[start..].find(close) {
+                    Some(close_start) => end = start + close_start + close.len(),
+                    None => break body.len(),
+                }
+            }
+        }
+        None => body.split_inclusive('\n')
+            .take_while(|line| line.trim().is_empty() || line.trim_start().starts_with(comment_syntax))
+            .map(str::len)
+            .sum(),
+    };
+    &body[..end]
+}
+
+/// Whether the comments at the top of the file contain the preamble,
+/// whatever their line endings. The preamble found further down, such as
+/// license text quoted in a string, doesn't count.
+pub fn contains_preamble(file_contents: &str, prefixed_preamble: &str, comment_syntax: &str) -> bool {
+    let leading_comments = leading_comments(file_contents, comment_syntax);
+    leading_comments.contains(prefixed_preamble) || leading_comments.replace("\r\n", "\n").contains(prefixed_preamble)
+}
+
+/// Puts back the lines taken with [`split_prologue`], separated from the
+/// rest by one blank line.
+pub fn join_prologue(prologue: &str, rest: &str) -> String {
+    let eol = line_ending(prologue);
+    match prologue.strip_prefix(BOM).unwrap_or(prologue) {
+        "" => format!("{prologue}{rest}"),
+        _ if prologue.ends_with('\n') => format!("{prologue}{eol}{rest}"),
+        _ => format!("{prologue}{eol}{eol}{rest}"),
+    }
+}
+
+/// Inserts `header` at the top of the file, after the lines that have to
+/// stay first, with the line endings the file uses. The blank lines the
+/// file started with are replaced by `blank_lines` ones, and an empty file
+/// only gets the header and a line ending.
+pub fn insert_header(file_contents: &str, header: &str, blank_lines: usize, comment_syntax: &str) -> String {
+    let eol = line_ending(file_contents);
+    let header = header.replace('\n', eol);
+    let (prologue, rest) = split_prologue(file_contents, comment_syntax);
+    let rest = rest.trim_start_matches(['\r', '\n']);
+    if rest.is_empty() {
+        return join_prologue(prologue, &format!("{header}{eol}"));
+    }
+    join_prologue(prologue, &format!("{header}{}{rest}", eol.repeat(blank_lines + 1)))
+}
+
+/// Takes out the `header` that [`insert_header`] put in, along with the
+/// blank line it left after the lines that have to stay first and up to
+/// `blank_lines` blank lines after the header, or `None` if the file
+/// doesn't start with it.
+pub fn remove_header(file_contents: &str, header: &str, blank_lines: usize, comment_syntax: &str) -> Option<String> {
+    let (prologue, _) = split_prologue(file_contents, comment_syntax);
+    let mut body = &file_contents[prologue.len()..];
+    if !prologue.strip_prefix(BOM).unwrap_or(prologue).is_empty() {
+        if !prologue.ends_with('\n') {
+            body = strip_line_ending(body).unwrap_or(body);
+        }
+        body = strip_line_ending(body).unwrap_or(body);
+    }
+    let rest = strip_preamble(body, header, blank_lines)?;
+    Some(format!("{prologue}{rest}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "// Copyright 2024 Me\n// MIT licensed";
+
+    #[test]
+    fn insert_header_keeps_lf_line_endings() {
+        let file_contents = "let a = 1;\nlet b = 2;\n";
+        let new_contents = insert_header(file_contents, HEADER, 1, "//");
+        assert_eq!(new_contents, "// Copyright 2024 Me\n// MIT licensed\n\nlet a = 1;\nlet b = 2;\n");
+        assert!(contains_preamble(&new_contents, HEADER, "//"));
+        assert_eq!(remove_header(&new_contents, HEADER, 1, "//").as_deref(), Some(file_contents));
+    }
+
+    #[test]
+    fn insert_header_keeps_crlf_line_endings() {
+        let file_contents = "let a = 1;\r\nlet b = 2;\r\n";
+        let new_contents = insert_header(file_contents, HEADER, 1, "//");
+        assert_eq!(new_contents, "// Copyright 2024 Me\r\n// MIT licensed\r\n\r\nlet a = 1;\r\nlet b = 2;\r\n");
+        assert!(contains_preamble(&new_contents, HEADER, "//"));
+        assert!(presence(&new_contents, HEADER, "//", false) == Presence::Present);
+        assert_eq!(remove_header(&new_contents, HEADER, 1, "//").as_deref(), Some(file_contents));
+    }
+
+    #[test]
+    fn insert_header_uses_the_dominant_line_ending_of_mixed_files() {
+        let file_contents = "let a = 1;\r\nlet b = 2;\nlet c = 3;\r\n";
+        let new_contents = insert_header(file_contents, HEADER, 1, "//");
+        assert_eq!(new_contents, format!("// Copyright 2024 Me\r\n// MIT licensed\r\n\r\n{file_contents}"));
+        assert_eq!(remove_header(&new_contents, HEADER, 1, "//").as_deref(), Some(file_contents));
+    }
+
+    #[test]
+    fn strip_preamble_matches_either_line_ending() {
+        assert_eq!(strip_preamble("// Copyright 2024 Me\r\n// MIT licensed\r\n\r\nrest", HEADER, 1), Some("rest"));
+        assert_eq!(strip_preamble("// Copyright 2024 Me\n// MIT licensed\n\nrest", HEADER, 1), Some("rest"));
+    }
+
+    #[test]
+    fn insert_header_goes_after_shebangs_coding_lines_and_boms() {
+        let header = "# Copyright 2024 Me";
+        for bom in ["", "\u{feff}"] {
+            for shebang in ["", "#!/usr/bin/env python3\n"] {
+                for coding in ["", "# -*- coding: utf-8 -*-\n"] {
+                    let prologue = format!("{bom}{shebang}{coding}");
+                    let file_contents = format!("{prologue}print(1)\n");
+                    assert_eq!(split_prologue(&file_contents, "#"), (prologue.as_str(), "print(1)\n"));
+
+                    let separator = if shebang.is_empty() && coding.is_empty() { "" } else { "\n" };
+                    let new_contents = insert_header(&file_contents, header, 1, "#");
+                    assert_eq!(new_contents, format!("{prologue}{separator}{header}\n\nprint(1)\n"));
+                    assert!(presence(&new_contents, header, "#", false) == Presence::Present);
+                    assert_eq!(remove_header(&new_contents, header, 1, "#"), Some(file_contents));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn front_matter_is_only_taken_in_markdown() {
+        let file_contents = "---\ntitle: Hello\n---\n\n# Hello\n";
+        assert_eq!(split_prologue(file_contents, "<!-- -->"), ("---\ntitle: Hello\n---\n", "# Hello\n"));
+        assert_eq!(split_prologue("---\na: 1\n---\nb: 2\n", "#").0, "");
+    }
+
+    #[test]
+    fn coding_lines_are_only_taken_from_the_first_two_lines() {
+        assert_eq!(split_prologue("#!/bin/sh\n# coding=latin-1\nx\n", "#").0, "#!/bin/sh\n# coding=latin-1\n");
+        assert_eq!(split_prologue("x = 1\n# coding: utf-8\n", "#").0, "");
+        assert_eq!(split_prologue("#!/bin/sh\nx\n# coding: utf-8\n", "#").0, "#!/bin/sh\n");
+    }
+
+    #[test]
+    fn coding_lines_only_count_in_languages_that_have_them() {
+        let file_contents = "# -*- coding: utf-8 -*-\nx\n";
+        assert_eq!(split_prologue(file_contents, "#").0, "# -*- coding: utf-8 -*-\n");
+        assert_eq!(split_prologue(file_contents, "//").0, "");
+    }
+
+    #[test]
+    fn inner_attributes_arent_shebangs() {
+        let files = ["#![deny(warnings)]\nfn main() {}\n", "#![cfg_attr(\n    test,\n    deny(warnings)\n)]\nfn main() {}\n"];
+        for file_contents in files {
+            assert_eq!(split_prologue(file_contents, "//"), ("", file_contents));
+            let new_contents = insert_header(file_contents, HEADER, 1, "//");
+            assert_eq!(new_contents, format!("{HEADER}\n\n{file_contents}"));
+            assert_eq!(remove_header(&new_contents, HEADER, 1, "//").as_deref(), Some(file_contents));
+        }
+        assert_eq!(split_prologue("#! /bin/sh\necho\n", "#").0, "#! /bin/sh\n");
+    }
+
+    #[test]
+    fn adding_twice_to_a_bom_file_is_idempotent_and_removable() {
+        for file_contents in ["\u{feff}let a = 1;\n", "\u{feff}let a = 1;\r\nlet b = 2;\r\n"] {
+            let new_contents = insert_header(file_contents, HEADER, 1, "//");
+            assert!(new_contents.starts_with("\u{feff}// Copyright 2024 Me"));
+            // `add` leaves files that already contain the preamble alone.
+            assert!(contains_preamble(&new_contents, HEADER, "//"));
+            assert_eq!(remove_header(&new_contents, HEADER, 1, "//").as_deref(), Some(file_contents));
+        }
+    }
+
+    const SVG_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+    const SVG_DOCTYPE: &str = "<!DOCTYPE svg PUBLIC \"-//W3C//DTD SVG 1.1//EN\"\n  \"http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd\">\n";
+    const SVG: &str = "<svg xmlns=\"http://www.w3.org/2000/svg\"/>\n";
+
+    #[test]
+    fn split_prologue_keeps_the_xml_declaration_and_doctype_first() {
+        let cases = [
+            (String::new(), SVG.to_string()),
+            (SVG_DECLARATION.to_string(), SVG.to_string()),
+            (SVG_DOCTYPE.to_string(), SVG.to_string()),
+            (format!("{SVG_DECLARATION}{SVG_DOCTYPE}"), SVG.to_string()),
+        ];
+        let header = prefix_preamble("Copyright 2024 Me", "<!-- -->");
+        for (prologue, rest) in cases {
+            let file_contents = format!("{prologue}{rest}");
+            assert_eq!(split_prologue(&file_contents, "<!-- -->"), (prologue.as_str(), rest.as_str()));
+
+            let separator = if prologue.is_empty() { "" } else { "\n" };
+            let new_contents = insert_header(&file_contents, &header, 1, "<!-- -->");
+            assert_eq!(new_contents, format!("{prologue}{separator}<!--\nCopyright 2024 Me\n-->\n\n{rest}"));
+        }
+    }
+
+    #[test]
+    fn split_prologue_takes_doctypes_with_an_internal_subset() {
+        let doctype = "<!DOCTYPE svg [\n  <!ENTITY ns \"http://www.w3.org/2000/svg\">\n]>\n";
+        let prologue = format!("{SVG_DECLARATION}{doctype}");
+        let file_contents = format!("{prologue}\n{SVG}");
+        assert_eq!(split_prologue(&file_contents, "<!-- -->"), (prologue.as_str(), SVG));
+    }
+
+    #[test]
+    fn vue_and_svelte_components_round_trip() {
+        let vue = "<template>\n  <p>{{ message }}</p>\n</template>\n\n<script setup>\nconst message = 'hi'\n</script>\n";
+        let svelte = "<script>\n  let name = 'world';\n</script>\n\n<h1>Hello {name}!</h1>\n";
+        let header = prefix_preamble("Copyright 2024 Me\nMIT licensed", "<!-- -->");
+        for component in [vue, svelte] {
+            let new_contents = insert_header(component, &header, 1, "<!-- -->");
+            assert_eq!(new_contents, format!("<!--\nCopyright 2024 Me\nMIT licensed\n-->\n\n{component}"));
+            // The component's blocks are untouched after the comment.
+            assert!(new_contents.ends_with(component));
+            assert!(presence(&new_contents, &header, "<!-- -->", false) == Presence::Present);
+            assert_eq!(remove_header(&new_contents, &header, 1, "<!-- -->").as_deref(), Some(component));
+        }
+    }
+}
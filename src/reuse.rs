@@ -0,0 +1,105 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Headers and `.license` companion files following the REUSE specification.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::preamble::{has_spdx_header, insert_header, prefix_preamble};
+use crate::{read_source_file_or_warn, scan_source_roots, WalkOptions};
+
+/// The extension of the companion files holding the headers of files that
+/// can't contain comments.
+const COMPANION_EXTENSION: &str = "license";
+
+fn companion_path(path: &Path) -> PathBuf {
+    let mut companion = path.as_os_str().to_owned();
+    companion.push(format!(".{COMPANION_EXTENSION}"));
+    PathBuf::from(companion)
+}
+
+/// Whether a file is covered by a header or a companion file.
+fn is_compliant(path: &Path) -> bool {
+    companion_path(path).is_file()
+        || std::fs::read_to_string(path).is_ok_and(|contents| has_spdx_header(&contents))
+}
+
+/// Lists every file under the source roots, except for companion files.
+fn reuse_files(config: &Config, source_root: Option<Vec<String>>) -> (Vec<(PathBuf, &str)>, Vec<PathBuf>) {
+    let source_files = scan_source_roots(config, source_root, &WalkOptions::default());
+    let unsupported = source_files.unsupported.into_iter()
+        .filter(|path| path.extension().and_then(|s| s.to_str()) != Some(COMPANION_EXTENSION))
+        .collect();
+    (source_files.files, unsupported)
+}
+
+/// Prints the files that are neither commented with an SPDX header nor have
+/// a companion file, returning whether there were none.
+pub fn reuse_check(config: &Config, source_root: Option<Vec<String>>) -> bool {
+    let (files, unsupported) = reuse_files(config, source_root);
+    let mut failures = 0;
+    for path in files.into_iter().map(|(path, _)| path).chain(unsupported) {
+        if !is_compliant(&path) {
+            println!("{}", path.display());
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{failures} file(s) have no copyright and licensing information, run `reuse` to fix them");
+        return false;
+    }
+    true
+}
+
+/// Adds the `SPDX-FileCopyrightText` and `SPDX-License-Identifier` lines to
+/// every file that can contain comments, and writes companion files for the
+/// ones that can't. Files that are already compliant are skipped.
+pub fn reuse(config: &Config, spdx_id: &str, copyright: &str, source_root: Option<Vec<String>>) {
+    let lines = [
+        format!("SPDX-FileCopyrightText: {copyright}"),
+        format!("SPDX-License-Identifier: {spdx_id}"),
+    ];
+
+    let (files, unsupported) = reuse_files(config, source_root);
+    for (path, comment_syntax) in files {
+        if is_compliant(&path) {
+            continue;
+        }
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        let header = prefix_preamble(&lines.join("\n"), comment_syntax);
+        println!("Adding REUSE header to file {path:?}");
+        std::fs::write(&path, insert_header(&file_contents, &header, config.blank_lines, comment_syntax)).unwrap();
+    }
+
+    for path in unsupported {
+        if is_compliant(&path) {
+            continue;
+        }
+        let companion = companion_path(&path);
+        println!("Writing {companion:?}");
+        std::fs::write(&companion, format!("{}\n", lines.join("\n"))).unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Finding the PREAMBLE that applies to each source file, so that the
+//! packages of a monorepo can each have their own.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::read_preamble;
+use crate::template::render_preamble;
+
+/// The PREAMBLEs applying to source files, each read and rendered once.
+pub struct Preambles<'a> {
+    config: &'a Config,
+    /// The PREAMBLE path relative to each directory.
+    preamble_path: &'a Path,
+    variables: &'a [(String, String)],
+    rendered: BTreeMap<PathBuf, String>,
+}
+
+impl<'a> Preambles<'a> {
+    pub fn new(config: &'a Config, preamble_path: &'a Path, variables: &'a [(String, String)]) -> Preambles<'a> {
+        Preambles { config, preamble_path, variables, rendered: BTreeMap::new() }
+    }
+
+    /// The nearest PREAMBLE in the directories containing `path`, up to the
+    /// current directory for relative paths.
+    pub fn find(&self, path: &Path) -> Option<PathBuf> {
+        path.parent()?.ancestors()
+            .map(|dir| dir.join(self.preamble_path))
+            .find(|candidate| candidate.symlink_metadata().is_ok())
+    }
+
+    /// The path and rendered contents of the PREAMBLE applying to `path`,
+    /// `None` if there's none. `is_new` is set when it's the first file
+    /// using it.
+    pub fn get(&mut self, path: &Path) -> Option<(PathBuf, &str, bool)> {
+        let preamble_path = self.find(path)?;
+        let is_new = !self.rendered.contains_key(&preamble_path);
+        let rendered = self.rendered.entry(preamble_path.clone())
+            .or_insert_with(|| render_preamble(self.config, &read_preamble(&preamble_path), self.variables));
+        Some((preamble_path, rendered, is_new))
+    }
+}
@@ -0,0 +1,116 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Searching the embedded licenses' metadata.
+
+use std::io::IsTerminal;
+use yaml_front_matter::Document;
+
+use crate::license::LicenseInfo;
+
+/// The searchable fields of a license, by name.
+fn fields(info: &LicenseInfo) -> Vec<(&'static str, String)> {
+    vec![
+        ("title", info.title.clone()),
+        ("description", info.description.trim().to_string()),
+        ("permissions", info.permissions.join(", ")),
+        ("conditions", info.conditions.join(", ")),
+        ("limitations", info.limitations.join(", ")),
+    ]
+}
+
+/// Wraps every case-insensitive occurrence of the terms in bold.
+fn highlight(text: &str, terms: &[String]) -> String {
+    let lowercase = text.to_lowercase();
+    // Lowercasing can change byte lengths outside of ASCII, in which case the
+    // offsets wouldn't line up with the original text.
+    if lowercase.len() != text.len() {
+        return text.to_string();
+    }
+
+    let mut highlighted = vec![false; text.len()];
+    for term in terms {
+        for (start, _) in lowercase.match_indices(term.as_str()) {
+            highlighted[start..start + term.len()].iter_mut().for_each(|h| *h = true);
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut bold = false;
+    for (index, c) in text.char_indices() {
+        if highlighted[index] != bold {
+            bold = highlighted[index];
+            result += if bold { "\x1b[1m" } else { "\x1b[0m" };
+        }
+        result.push(c);
+    }
+    if bold {
+        result += "\x1b[0m";
+    }
+    result
+}
+
+/// Prints the licenses where every term occurs in some field, along with the
+/// fields that matched. Returns whether any license matched.
+pub fn search(licenses: &[Document<LicenseInfo>], terms: &[String], json: bool) -> bool {
+    let terms: Vec<String> = terms.iter().map(|term| term.to_lowercase()).collect();
+
+    let mut matches = vec![];
+    for document in licenses {
+        let info = &document.metadata;
+        let fields = fields(info);
+        let all_found = terms.iter().all(|term| {
+            fields.iter().any(|(_, text)| text.to_lowercase().contains(term.as_str()))
+        });
+        if !all_found {
+            continue;
+        }
+        let matched: Vec<(&str, String)> = fields.into_iter()
+            .filter(|(_, text)| terms.iter().any(|term| text.to_lowercase().contains(term.as_str())))
+            .collect();
+        matches.push((info, matched));
+    }
+
+    if json {
+        let results: Vec<serde_json::Value> = matches.iter().map(|(info, matched)| {
+            serde_json::json!({
+                "title": info.title,
+                "spdx-id": info.spdx_id,
+                "matched": matched.iter()
+                    .map(|(name, text)| (name.to_string(), serde_json::Value::from(text.as_str())))
+                    .collect::<serde_json::Map<_, _>>(),
+            })
+        }).collect();
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+    } else {
+        let colored = std::io::stdout().is_terminal();
+        for (info, matched) in &matches {
+            println!("{:<60}   -  short:  {:}", info.title, info.spdx_id);
+            for (name, text) in matched {
+                let text = if colored { highlight(text, &terms) } else { text.clone() };
+                println!("  {name}: {text}");
+            }
+        }
+    }
+
+    !matches.is_empty()
+}
@@ -0,0 +1,119 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Summary of which files have the preamble.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::generated::{has_ignore_directive, is_generated, looks_minified};
+use crate::preamble::{
+    looks_like_notice, prefix_preamble, presence, split_leading_comment, split_prologue, starts_with_inline_html,
+    Presence,
+};
+use crate::{is_too_large, load_preamble, read_source_file_or_warn, source_files};
+
+/// The state of a source file's header.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Current,
+    /// The preamble is there with different copyright years.
+    Outdated,
+    Missing,
+    Different,
+    /// Opted out with the ignore directive.
+    OptedOut,
+    /// Left alone by `check`, such as generated or minified files.
+    Skipped,
+}
+
+impl Status {
+    /// Classifies a file the way `check` does.
+    fn of(config: &Config, path: &Path, file_contents: &str, prefixed_preamble: &str, comment_syntax: &str) -> Status {
+        if has_ignore_directive(file_contents) {
+            return Status::OptedOut;
+        }
+        let is_skipped = starts_with_inline_html(path, file_contents, comment_syntax)
+            || is_generated(config, file_contents)
+            || looks_minified(path, file_contents);
+        if is_skipped {
+            return Status::Skipped;
+        }
+        let presence = match &config.markers {
+            Some(markers) => markers.presence(file_contents, prefixed_preamble, comment_syntax),
+            None => presence(file_contents, prefixed_preamble, comment_syntax, false),
+        };
+        match presence {
+            Presence::Present => return Status::Current,
+            Presence::Outdated => return Status::Outdated,
+            Presence::Missing => {}
+        }
+        match split_leading_comment(split_prologue(file_contents, comment_syntax).1, comment_syntax) {
+            Some((header, _)) if looks_like_notice(header, comment_syntax) => Status::Different,
+            _ => Status::Missing,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Status::Current => "have the preamble",
+            Status::Outdated => "have an outdated preamble",
+            Status::Missing => "are missing the preamble",
+            Status::Different => "have a different or unknown header",
+            Status::OptedOut => "are opted out with `license-preamble: ignore`",
+            Status::Skipped => "are skipped as generated, minified or too large",
+        }
+    }
+}
+
+/// Classifies every source file without modifying anything, printing a count
+/// per status and, when `list_files` is set, the files themselves.
+pub fn status(config: &Config, preamble_path: &Path, source_root: Option<Vec<String>>, list_files: bool) {
+    let preamble_contents = load_preamble(config, preamble_path);
+
+    let mut classified: Vec<(Status, PathBuf)> = vec![];
+    for (path, comment_syntax) in source_files(config, source_root) {
+        if is_too_large(&path, config.max_file_size) {
+            classified.push((Status::Skipped, path));
+            continue;
+        }
+        let prefixed_preamble = prefix_preamble(&preamble_contents, comment_syntax);
+        let Some(file_contents) = read_source_file_or_warn(&path) else {
+            continue;
+        };
+        classified.push((Status::of(config, &path, &file_contents, &prefixed_preamble, comment_syntax), path));
+    }
+
+    let statuses = [Status::Current, Status::Outdated, Status::Missing, Status::Different, Status::OptedOut, Status::Skipped];
+    for status in statuses {
+        let paths: Vec<&PathBuf> = classified.iter()
+            .filter(|(file_status, _)| *file_status == status)
+            .map(|(_, path)| path)
+            .collect();
+        println!("{} file(s) {}", paths.len(), status.describe());
+        if list_files {
+            for path in paths {
+                println!("  {}", path.display());
+            }
+        }
+    }
+}
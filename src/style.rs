@@ -0,0 +1,130 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+
+//! Line and block comment styles for C-family languages.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The line comment syntax of C-family languages.
+const LINE_COMMENT: &str = "//";
+
+/// A block comment with ` * ` in front of every line.
+const BLOCK_COMMENT: &str = "/* * */";
+
+/// A JSDoc comment, which doc generators and API extractors read as the
+/// file's header.
+const JSDOC_COMMENT: &str = "/** * */";
+
+/// The renderings of C-family comments, which are told apart from each
+/// other when looking for the preamble so that switching styles doesn't add
+/// it twice.
+const C_FAMILY_SYNTAXES: [&str; 4] = [LINE_COMMENT, "/* */", BLOCK_COMMENT, JSDOC_COMMENT];
+
+/// The characters the border's rules may be drawn with.
+pub const RULE_CHARACTERS: [char; 3] = ['=', '-', '*'];
+
+/// Extensions of languages without line comments, which always use block
+/// comments.
+const BLOCK_ONLY_EXTENSIONS: [&str; 1] = ["css"];
+
+/// How the preamble is commented in languages with both line and block
+/// comments.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommentStyle {
+    /// `//` in front of every line
+    Line,
+    /// A `/* */` block comment with ` * ` in front of every line
+    Block,
+    /// A `/** */` JSDoc comment with ` * ` in front of every line
+    Jsdoc,
+}
+
+impl CommentStyle {
+    /// The comment syntax to use in this style for files of `extension`
+    /// instead of `comment_syntax`, or `None` if it stays the same.
+    pub fn comment_syntax(self, extension: &str, comment_syntax: &str) -> Option<&'static str> {
+        if !C_FAMILY_SYNTAXES.contains(&comment_syntax) {
+            return None;
+        }
+        match self {
+            CommentStyle::Line if BLOCK_ONLY_EXTENSIONS.contains(&extension) => None,
+            CommentStyle::Line => Some(LINE_COMMENT),
+            CommentStyle::Block => Some(BLOCK_COMMENT),
+            CommentStyle::Jsdoc => Some(JSDOC_COMMENT),
+        }
+    }
+}
+
+/// The comment syntaxes a file may have the preamble in, `comment_syntax`
+/// first.
+pub fn equivalent_syntaxes(comment_syntax: &str) -> Vec<&str> {
+    let mut syntaxes = vec![comment_syntax];
+    if C_FAMILY_SYNTAXES.contains(&comment_syntax) {
+        syntaxes.extend(C_FAMILY_SYNTAXES.iter().filter(|syntax| **syntax != comment_syntax));
+    }
+    syntaxes
+}
+
+/// The horizontal rules framing the preamble when `[border]` is set in the
+/// configuration file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Border {
+    /// The character the rules are drawn with, one of [`RULE_CHARACTERS`].
+    pub character: char,
+    /// How long the rules are, the length of the longest preamble line by
+    /// default.
+    pub width: Option<usize>,
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Border { character: '=', width: None }
+    }
+}
+
+impl Border {
+    /// Puts a rule before and after the rendered preamble.
+    pub fn frame(&self, preamble_contents: &str) -> String {
+        let width = self.width.unwrap_or_else(|| {
+            preamble_contents.lines().map(|line| line.trim_end().chars().count()).max().unwrap_or(0)
+        });
+        let rule = self.character.to_string().repeat(width.max(RULE_MIN_WIDTH));
+        format!("{rule}\n{}\n{rule}", preamble_contents.trim_end())
+    }
+}
+
+/// Rules shorter than this aren't told apart from text.
+const RULE_MIN_WIDTH: usize = 3;
+
+/// Whether `line` is a horizontal rule such as the border's, which isn't
+/// part of the preamble's text.
+pub fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    let mut characters = line.chars();
+    characters.next().is_some_and(|first| {
+        RULE_CHARACTERS.contains(&first) && line.chars().count() >= RULE_MIN_WIDTH && characters.all(|c| c == first)
+    })
+}
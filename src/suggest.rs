@@ -0,0 +1,134 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A small questionnaire narrowing down the embedded licenses.
+
+use std::io::{BufRead, Write};
+use clap::ValueEnum;
+
+use crate::license::LicenseInfo;
+
+/// Licenses approved by the Open Source Initiative, which isn't part of the
+/// choosealicense metadata.
+static OSI_APPROVED: [&str; 38] = [
+    "0BSD", "AFL-3.0", "AGPL-3.0", "Apache-2.0", "Artistic-2.0", "BlueOak-1.0.0",
+    "BSD-2-Clause", "BSD-2-Clause-Patent", "BSD-3-Clause", "BSL-1.0", "CECILL-2.1",
+    "CERN-OHL-P-2.0", "CERN-OHL-S-2.0", "CERN-OHL-W-2.0", "ECL-2.0", "EPL-1.0",
+    "EPL-2.0", "EUPL-1.1", "EUPL-1.2", "GPL-2.0", "GPL-3.0", "ISC", "LGPL-2.1",
+    "LGPL-3.0", "LPPL-1.3c", "MIT", "MIT-0", "MPL-2.0", "MS-PL", "MS-RL",
+    "MulanPSL-2.0", "NCSA", "OFL-1.1", "OSL-3.0", "PostgreSQL", "UPL-1.0",
+    "Unlicense", "Zlib",
+];
+
+/// What is being licensed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum)]
+pub enum Kind {
+    Software,
+    Documentation,
+    Hardware,
+    Data,
+    Font,
+}
+
+impl Kind {
+    fn of(info: &LicenseInfo) -> Kind {
+        match info.spdx_id.as_str() {
+            "CC-BY-4.0" | "CC-BY-SA-4.0" | "GFDL-1.3" => Kind::Documentation,
+            "CERN-OHL-P-2.0" | "CERN-OHL-S-2.0" | "CERN-OHL-W-2.0" => Kind::Hardware,
+            "ODbL-1.0" => Kind::Data,
+            "OFL-1.1" => Kind::Font,
+            _ => Kind::Software,
+        }
+    }
+}
+
+/// Answers to the questionnaire, `None` meaning "don't care".
+#[derive(Default, Debug)]
+pub struct Criteria {
+    pub copyleft: Option<bool>,
+    pub patent_grant: Option<bool>,
+    pub osi_approved: Option<bool>,
+    pub kind: Option<Kind>,
+}
+
+impl Criteria {
+    pub fn is_empty(&self) -> bool {
+        self.copyleft.is_none() && self.patent_grant.is_none() && self.osi_approved.is_none() && self.kind.is_none()
+    }
+
+    pub fn matches(&self, info: &LicenseInfo) -> bool {
+        let copyleft = info.conditions.iter().any(|condition| condition.starts_with("same-license"));
+        let patent_grant = info.permissions.iter().any(|permission| permission == "patent-use");
+        let osi_approved = OSI_APPROVED.contains(&info.spdx_id.as_str());
+
+        self.copyleft.is_none_or(|wanted| wanted == copyleft)
+            && self.patent_grant.is_none_or(|wanted| wanted == patent_grant)
+            && self.osi_approved.is_none_or(|wanted| wanted == osi_approved)
+            && self.kind.is_none_or(|wanted| wanted == Kind::of(info))
+    }
+
+    /// Fills in the criteria by asking questions on stdin.
+    pub fn ask() -> Criteria {
+        let kind = loop {
+            let answer = prompt("What are you licensing? (software/documentation/hardware/data/font, empty for any)");
+            if answer.is_empty() {
+                break None;
+            }
+            match Kind::from_str(&answer, true) {
+                Ok(kind) => break Some(kind),
+                Err(_) => eprintln!("Unknown kind {answer:?}"),
+            }
+        };
+
+        Criteria {
+            copyleft: ask_yes_no("Should derivative works have to use the same license (copyleft)?"),
+            patent_grant: ask_yes_no("Do you want an explicit patent grant?"),
+            osi_approved: ask_yes_no("Does it need to be OSI approved?"),
+            kind,
+        }
+    }
+}
+
+pub fn prompt(question: &str) -> String {
+    ask_text(question).to_lowercase()
+}
+
+/// Asks a question on stdin, returning the answer as typed.
+pub fn ask_text(question: &str) -> String {
+    print!("{question} ");
+    std::io::stdout().flush().unwrap();
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer).unwrap();
+    answer.trim().to_string()
+}
+
+/// Asks a yes/no question, an empty answer meaning "don't care".
+pub fn ask_yes_no(question: &str) -> Option<bool> {
+    loop {
+        match prompt(&format!("{question} [y/n/empty for any]")).as_str() {
+            "y" | "yes" => return Some(true),
+            "n" | "no" => return Some(false),
+            "" => return None,
+            answer => eprintln!("Unknown answer {answer:?}"),
+        }
+    }
+}
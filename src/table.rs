@@ -0,0 +1,93 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Printing aligned tables that fit the terminal.
+
+use std::io::IsTerminal;
+
+/// The width assumed when stdout is a terminal of unknown size.
+const DEFAULT_WIDTH: usize = 80;
+/// Columns are never truncated to less than this many characters.
+const MIN_COLUMN_WIDTH: usize = 5;
+/// The space between columns.
+const GAP: &str = "  ";
+
+/// The width of the terminal stdout is connected to, `None` if it isn't
+/// one. `COLUMNS` takes precedence when set.
+pub fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let columns = std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok());
+    columns.or_else(|| {
+        terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| usize::from(width))
+    }).or(Some(DEFAULT_WIDTH))
+}
+
+/// Shortens `text` to `width` characters, ending it with an ellipsis when
+/// it's cut.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Prints `rows` under `headers` with aligned columns, shrinking the widest
+/// columns to fit the terminal. When stdout isn't a terminal, the rows are
+/// printed as tab-separated values without the headers instead.
+pub fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let Some(terminal_width) = terminal_width() else {
+        for row in rows {
+            println!("{}", row.join("\t"));
+        }
+        return;
+    };
+
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let available = terminal_width.saturating_sub(GAP.len() * headers.len().saturating_sub(1));
+    while widths.iter().sum::<usize>() > available {
+        let widest = widths.iter_mut().max().unwrap();
+        if *widest <= MIN_COLUMN_WIDTH {
+            break;
+        }
+        *widest -= 1;
+    }
+
+    let print_row = |cells: &[String]| {
+        let line: Vec<String> = cells.iter().zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", truncate(cell, width)))
+            .collect();
+        println!("{}", line.join(GAP).trim_end());
+    };
+    print_row(&headers.iter().map(|header| header.to_string()).collect::<Vec<String>>());
+    for row in rows {
+        print_row(row);
+    }
+}
@@ -0,0 +1,116 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Rendering the `{{variable}}`s of PREAMBLE templates.
+
+use std::path::Path;
+
+use crate::bump_year::current_year;
+use crate::config::{Config, CONFIG_PATH};
+use crate::manifest::project_name;
+use crate::placeholders::git_config;
+
+/// Replaces every `{{name}}` in `template` with its value, failing with the
+/// names that have none.
+fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> Result<String, Vec<String>> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut unresolved: Vec<String> = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        rendered.push_str(&rest[..start]);
+        match value(name) {
+            Some(value) => rendered.push_str(&value),
+            None if !unresolved.iter().any(|unresolved| unresolved == name) => unresolved.push(name.to_string()),
+            None => {}
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+
+    if unresolved.is_empty() {
+        Ok(rendered)
+    } else {
+        Err(unresolved)
+    }
+}
+
+/// The value of a built-in variable: the current `year`, the `project`
+/// name from Cargo.toml or package.json and the copyright `holder` from the
+/// configuration file or git's `user.name`.
+fn builtin_variable(config: &Config, name: &str) -> Option<String> {
+    match name {
+        "year" => Some(current_year().to_string()),
+        "project" => project_name(Path::new("")),
+        "holder" => config.holder.clone().or_else(|| git_config("user.name")),
+        _ => None,
+    }
+}
+
+/// Renders PREAMBLE, taking the variables from `variables`, then the
+/// configuration file, then the built-in ones. Fails with the variables that
+/// have no value. The result is framed by the border and wrapped in the
+/// marker lines when they're enabled.
+pub fn try_render_preamble(
+    config: &Config,
+    preamble_contents: &str,
+    variables: &[(String, String)],
+) -> Result<String, Vec<String>> {
+    let value = |name: &str| {
+        variables.iter().rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .or_else(|| config.variables.get(name).cloned())
+            .or_else(|| builtin_variable(config, name))
+    };
+    let mut rendered = render(preamble_contents, value)?;
+    if let Some(border) = &config.border {
+        rendered = border.frame(&rendered);
+    }
+    Ok(match &config.markers {
+        Some(markers) => markers.wrap(&rendered),
+        None => rendered,
+    })
+}
+
+/// Renders PREAMBLE like [`try_render_preamble`], exiting with an error
+/// listing the variables that have no value.
+pub fn render_preamble(config: &Config, preamble_contents: &str, variables: &[(String, String)]) -> String {
+    try_render_preamble(config, preamble_contents, variables).unwrap_or_else(|unresolved| {
+        eprintln!("PREAMBLE uses variables without a value: {}", unresolved.join(", "));
+        eprintln!("Pass them with `--var <NAME>=<VALUE>` or set them under [variables] in {CONFIG_PATH}");
+        std::process::exit(1);
+    })
+}
+
+/// Parses a `--var` argument.
+pub fn parse_variable(argument: &str) -> Result<(String, String), String> {
+    let (name, value) = argument.split_once('=').ok_or("expected <NAME>=<VALUE>")?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(String::from("the variable name is empty"));
+    }
+    Ok((name.to_string(), value.to_string()))
+}
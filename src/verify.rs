@@ -0,0 +1,210 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Detection of hand edits to the LICENSE file.
+
+use std::path::Path;
+use yaml_front_matter::Document;
+
+use crate::config::Config;
+use crate::identify::identify;
+use crate::license::{find_license, LicenseInfo};
+use crate::license_files;
+
+/// A line of the canonical text, split around its `[placeholder]` spans.
+struct Pattern<'a> {
+    line: &'a str,
+    segments: Vec<&'a str>,
+}
+
+impl<'a> Pattern<'a> {
+    fn new(line: &'a str) -> Pattern<'a> {
+        let mut segments = vec![];
+        let mut rest = line;
+        while let Some((before, after)) = rest.split_once('[').and_then(|(before, after)| {
+            after.split_once(']').map(|(_, after)| (before, after))
+        }) {
+            segments.push(before);
+            rest = after;
+        }
+        segments.push(rest);
+        Pattern { line, segments }
+    }
+
+    fn has_placeholders(&self) -> bool {
+        self.segments.len() > 1
+    }
+
+    /// Whether `line` is this line, with any text in place of the
+    /// placeholders.
+    fn matches(&self, line: &str) -> bool {
+        if !self.has_placeholders() {
+            return self.line == line;
+        }
+        let (first, rest) = self.segments.split_first().unwrap();
+        let Some(mut remaining) = line.strip_prefix(first) else {
+            return false;
+        };
+        let (last, middle) = rest.split_last().unwrap();
+        for segment in middle {
+            match remaining.find(segment) {
+                Some(index) => remaining = &remaining[index + segment.len()..],
+                None => return false,
+            }
+        }
+        remaining.ends_with(last)
+    }
+}
+
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Lines only in the canonical text and lines only in the project's LICENSE,
+/// by the longest common subsequence of non-blank lines.
+fn diff(canonical: &[Pattern], lines: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut lengths = vec![vec![0; lines.len() + 1]; canonical.len() + 1];
+    for (i, pattern) in canonical.iter().enumerate().rev() {
+        for (j, line) in lines.iter().enumerate().rev() {
+            lengths[i][j] = if pattern.matches(line) {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut missing, mut extra) = (vec![], vec![]);
+    let (mut i, mut j) = (0, 0);
+    while i < canonical.len() && j < lines.len() {
+        if canonical[i].matches(&lines[j]) {
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            missing.push(canonical[i].line.to_string());
+            i += 1;
+        } else {
+            extra.push(lines[j].clone());
+            j += 1;
+        }
+    }
+    missing.extend(canonical[i..].iter().map(|pattern| pattern.line.to_string()));
+    extra.extend(lines[j..].iter().cloned());
+    (missing, extra)
+}
+
+/// Compares LICENSE with the canonical text of the configured license, or
+/// of the closest one, printing the divergent lines. With `fix`, LICENSE is
+/// rewritten to the canonical text, keeping its filled-in placeholder lines
+/// such as the copyright line. The files of a dual license are each
+/// compared with the closest license. Returns whether every file matched.
+pub fn verify_license(
+    licenses: &[Document<LicenseInfo>],
+    config: &Config,
+    license_path: &Path,
+    fix: bool,
+) -> bool {
+    if config.custom_license {
+        println!("LICENSE is a custom license, there's no canonical text to compare it with");
+        return true;
+    }
+    let license_paths = license_files(license_path);
+    // A dual license is configured as an expression rather than the
+    // license of a single file.
+    let configured = config.license.as_deref()
+        .filter(|_| license_paths.len() == 1)
+        .map(|license| find_license(licenses, license));
+    let mut matched = true;
+    for license_path in license_paths {
+        matched &= verify_license_file(licenses, configured, &license_path, fix);
+    }
+    matched
+}
+
+/// Compares one license file with `license_document`, or the closest
+/// license when `None`, like [`verify_license`].
+fn verify_license_file(
+    licenses: &[Document<LicenseInfo>],
+    license_document: Option<&Document<LicenseInfo>>,
+    license_path: &Path,
+    fix: bool,
+) -> bool {
+    let name = license_path.display();
+    let Ok(license_contents) = std::fs::read_to_string(license_path) else {
+        eprintln!("{name} is missing, run `init <license>`");
+        return false;
+    };
+    let license_document = match license_document {
+        Some(license_document) => license_document,
+        None => match identify(&license_contents, licenses) {
+            Some((license_document, _)) => license_document,
+            None => {
+                eprintln!("{name} doesn't resemble any known license");
+                return false;
+            }
+        },
+    };
+
+    let canonical_lines: Vec<String> = license_document.content.trim().lines().map(normalize).collect();
+    let canonical: Vec<Pattern> = canonical_lines.iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| Pattern::new(line))
+        .collect();
+    let lines: Vec<String> = license_contents.lines()
+        .map(normalize)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let (missing, extra) = diff(&canonical, &lines);
+    if missing.is_empty() && extra.is_empty() {
+        println!("{name} matches the {}", license_document.metadata.title);
+        return true;
+    }
+
+    println!("{name} differs from the {}:", license_document.metadata.title);
+    for line in &missing {
+        println!("- {line}");
+    }
+    for line in &extra {
+        println!("+ {line}");
+    }
+
+    if fix {
+        let fixed: Vec<&str> = license_document.content.trim().lines().map(|canonical_line| {
+            let normalized = normalize(canonical_line);
+            let pattern = Pattern::new(&normalized);
+            if !pattern.has_placeholders() {
+                return canonical_line;
+            }
+            license_contents.lines()
+                .find(|line| {
+                    let line = normalize(line);
+                    line != normalized && pattern.matches(&line)
+                })
+                .unwrap_or(canonical_line)
+        }).collect();
+        std::fs::write(license_path, fixed.join("\n")).unwrap();
+        println!("Rewrote {name} with the canonical text");
+        return true;
+    }
+    false
+}
@@ -0,0 +1,151 @@
+// MIT License
+// 
+// Copyright (c) 2024 Pedro Tacla Yamada
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Adding the preamble to files as they are created or saved.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::config::Config;
+use crate::preamble::prefix_preamble;
+use crate::scopes::Preambles;
+use crate::{
+    add_decision, expand_source_roots, is_too_large, read_source_file_or_warn, scan_source_roots, AddDecision,
+    AddRules, WalkOptions,
+};
+
+/// How long a file must go without changes before it's considered saved.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How often pending files and Ctrl-C are checked for.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Watches the source roots until Ctrl-C, adding the preamble to the files
+/// `add` would add it to, then prints the files that were touched.
+pub fn watch(config: &Config, preamble_path: &Path, source_root: Option<Vec<String>>) {
+    let mut preambles = Preambles::new(config, preamble_path, &[]);
+    let source_roots = expand_source_roots(source_root.unwrap_or_else(|| config.default_source_roots()));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = stop.clone();
+    if let Err(err) = ctrlc::set_handler(move || handler_stop.store(true, Ordering::SeqCst)) {
+        eprintln!("Couldn't set up the Ctrl-C handler: {err}");
+        std::process::exit(1);
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).unwrap_or_else(|err| {
+        eprintln!("Couldn't start watching for changes: {err}");
+        std::process::exit(1);
+    });
+    for source_root in &source_roots {
+        if std::fs::metadata(source_root).is_err() {
+            continue;
+        }
+        match watcher.watch(Path::new(source_root), RecursiveMode::Recursive) {
+            Ok(()) => println!("Watching {source_root}"),
+            Err(err) => eprintln!("Warning: couldn't watch {source_root}, {err}"),
+        }
+    }
+
+    // Paths waiting for their changes to settle, by the time of the last
+    // change.
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    // What was last written to each file, so the events caused by our own
+    // writes are ignored.
+    let mut written: HashMap<PathBuf, String> = HashMap::new();
+    let mut touched: Vec<PathBuf> = vec![];
+    while !stop.load(Ordering::SeqCst) {
+        match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+            }
+            Ok(Err(err)) => eprintln!("Watch error: {err}"),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        let settled: Vec<PathBuf> = pending.iter()
+            .filter(|(_, changed_at)| changed_at.elapsed() >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        if settled.is_empty() {
+            continue;
+        }
+        // Walking the source roots again tells which files `add` would
+        // pick, leaving out the excluded, ignored and vendored ones. Events
+        // have absolute paths while the walk keeps the roots as given, so
+        // both are compared canonicalized.
+        let walked = scan_source_roots(config, Some(source_roots.clone()), &WalkOptions::default());
+        let selected: HashMap<PathBuf, (PathBuf, &str)> = walked.files.into_iter()
+            .filter_map(|(path, comment_syntax)| Some((std::fs::canonicalize(&path).ok()?, (path, comment_syntax))))
+            .collect();
+        for path in settled {
+            pending.remove(&path);
+            let canonical_path = std::fs::canonicalize(&path).ok();
+            let Some((walked_path, comment_syntax)) = canonical_path.and_then(|path| selected.get(&path)) else {
+                continue;
+            };
+            let Some((_, preamble_contents, _)) = preambles.get(walked_path) else {
+                continue;
+            };
+            let prefixed_preamble = prefix_preamble(preamble_contents, comment_syntax);
+            if is_too_large(&path, config.max_file_size) {
+                continue;
+            }
+            let Some(file_contents) = read_source_file_or_warn(&path) else {
+                continue;
+            };
+            if written.get(&path) == Some(&file_contents) {
+                continue;
+            }
+            let rules = AddRules::default();
+            let new_contents = match add_decision(config, &path, &file_contents, &prefixed_preamble, comment_syntax, &rules) {
+                AddDecision::Add(new_contents) | AddDecision::Replace(new_contents) => new_contents,
+                _ => continue,
+            };
+
+            println!("Adding preamble to file {path:?}");
+            if let Err(err) = std::fs::write(&path, &new_contents) {
+                eprintln!("Failed to write {path:?}: {err}");
+                continue;
+            }
+            written.insert(path.clone(), new_contents);
+            if !touched.contains(&path) {
+                touched.push(path);
+            }
+        }
+    }
+
+    println!("Added the preamble to {} file(s)", touched.len());
+    for path in touched {
+        println!("  {}", path.display());
+    }
+}